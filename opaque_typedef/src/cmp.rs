@@ -0,0 +1,102 @@
+//! Comparison functions used by generated codes.
+
+/// Comparison functions which ignore ASCII case.
+///
+/// These functions are used by `opaque_typedef_macros` for types with
+/// `#[opaque_typedef(cmp(ascii_case_insensitive))]`.
+/// They are consistent with each other, i.e. `eq(a, b)` is equivalent to
+/// `cmp(a, b) == Ordering::Equal`, and equal strings have the same hash.
+pub mod ascii_case_insensitive {
+    /// Checks whether the two strings are equal, ignoring ASCII case.
+    pub fn eq(lhs: &str, rhs: &str) -> bool {
+        lhs.eq_ignore_ascii_case(rhs)
+    }
+
+    /// Compares the two strings, ignoring ASCII case.
+    pub fn partial_cmp(lhs: &str, rhs: &str) -> Option<core::cmp::Ordering> {
+        Some(cmp(lhs, rhs))
+    }
+
+    /// Compares the two strings, ignoring ASCII case.
+    pub fn cmp(lhs: &str, rhs: &str) -> core::cmp::Ordering {
+        let lhs = lhs.bytes().map(|b| b.to_ascii_lowercase());
+        let rhs = rhs.bytes().map(|b| b.to_ascii_lowercase());
+        lhs.cmp(rhs)
+    }
+
+    /// Feeds the string into the given hasher, ignoring ASCII case.
+    pub fn hash<H: core::hash::Hasher>(s: &str, state: &mut H) {
+        for b in s.bytes() {
+            core::hash::Hash::hash(&b.to_ascii_lowercase(), state);
+        }
+        // Same as `str::hash`, to avoid prefix collisions.
+        core::hash::Hash::hash(&0xffu8, state);
+    }
+}
+
+/// Comparison functions for floating point numbers which are not NaN.
+///
+/// These functions are used by `opaque_typedef_macros` for types with
+/// `#[opaque_typedef(float(not_nan))]`.
+/// They are consistent with each other, and `0.0` and `-0.0` are equal and
+/// have the same hash.
+pub mod not_nan {
+    use float::Float;
+
+    /// Checks whether the two numbers are equal.
+    pub fn eq<T: Float>(lhs: &T, rhs: &T) -> bool {
+        *lhs == *rhs
+    }
+
+    /// Compares the two numbers.
+    pub fn partial_cmp<T: Float>(lhs: &T, rhs: &T) -> Option<core::cmp::Ordering> {
+        lhs.partial_cmp(rhs)
+    }
+
+    /// Compares the two numbers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the numbers is NaN.
+    pub fn cmp<T: Float>(lhs: &T, rhs: &T) -> core::cmp::Ordering {
+        lhs.partial_cmp(rhs)
+            .expect("Should never happen: NaN should be rejected by the validator")
+    }
+
+    /// Feeds the number into the given hasher.
+    pub fn hash<T: Float, H: core::hash::Hasher>(v: &T, state: &mut H) {
+        state.write_u64(v.to_normalized_bits());
+    }
+}
+
+/// Constant-time equality comparison.
+///
+/// This function is used by `opaque_typedef_macros` for types with
+/// `#[opaque_typedef(cmp(constant_time))]`.
+pub mod constant_time {
+    /// Checks whether the two byte sequences are equal, in time independent
+    /// of their contents.
+    ///
+    /// # Length leak
+    ///
+    /// The lengths are not hidden: values with different lengths are
+    /// unequal immediately, so the timing reveals whether the lengths are
+    /// equal (and the time for equal lengths reveals the length itself).
+    /// If the length is secret, compare fixed-length values such as
+    /// hashes or MACs of them instead.
+    pub fn eq<T: AsRef<[u8]> + ?Sized>(lhs: &T, rhs: &T) -> bool {
+        let lhs = lhs.as_ref();
+        let rhs = rhs.as_ref();
+        if lhs.len() != rhs.len() {
+            return false;
+        }
+        let mut diff = 0u8;
+        for (&l, &r) in lhs.iter().zip(rhs) {
+            // Hide the accumulator from the optimizer on every iteration,
+            // so that the loop cannot be turned into an early return once
+            // `diff` becomes nonzero.
+            diff = core::hint::black_box(diff | (l ^ r));
+        }
+        core::hint::black_box(diff) == 0
+    }
+}
@@ -0,0 +1,59 @@
+//! Floating point number support.
+//!
+//! These items are used by `opaque_typedef_macros` for types with
+//! `#[opaque_typedef(float(not_nan))]`.
+
+/// Floating point number types.
+///
+/// This trait is implemented for `f32` and `f64`.
+pub trait Float: Copy + PartialOrd {
+    /// Returns `true` if the value is NaN.
+    fn is_nan(self) -> bool;
+    /// Returns the bits of the value, with negative zero normalized to
+    /// positive zero.
+    fn to_normalized_bits(self) -> u64;
+}
+
+macro_rules! impl_float {
+    ($ty:ty) => {
+        impl Float for $ty {
+            fn is_nan(self) -> bool {
+                <$ty>::is_nan(self)
+            }
+
+            fn to_normalized_bits(self) -> u64 {
+                if self == 0.0 {
+                    // Both `0.0` and `-0.0`.
+                    0
+                } else {
+                    u64::from(self.to_bits())
+                }
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+/// An error type that indicates the value is NaN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NanError;
+
+impl core::fmt::Display for NanError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("NaN is not allowed")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NanError {}
+
+/// Validates that the value is not NaN.
+pub fn reject_nan<T: Float>(v: T) -> Result<T, NanError> {
+    if v.is_nan() {
+        Err(NanError)
+    } else {
+        Ok(v)
+    }
+}
@@ -1,21 +1,174 @@
 //! Traits for `opaque_typedef_macros`.
+//!
+//! This crate is `#![no_std]` if the default `std` feature is disabled.
 #![warn(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+extern crate alloc;
+
+// Re-exported for generated codes.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub extern crate serde;
+
+pub mod cmp;
+pub mod float;
+pub mod ops;
+pub mod secret;
+pub mod validator;
 
 /// An error type that indicates the error should never happen.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Infallible {}
 
+impl core::fmt::Display for Infallible {
+    fn fmt(&self, _: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match *self {}
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Infallible {}
+
+/// An error type for parsing opaque typedef-ed values from strings.
+///
+/// This is used as `FromStr::Err` of types with `FromStr` derive target.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ParseError<P, V> {
+    /// The string does not match the format specified by
+    /// `#[opaque_typedef(display_fmt = ..)]`.
+    Format,
+    /// Failed to parse the inner value.
+    Inner(P),
+    /// The inner value is parsed but failed to be validated.
+    Validation(V),
+}
+
+impl<P: core::fmt::Display, V: core::fmt::Display> core::fmt::Display for ParseError<P, V> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match *self {
+            ParseError::Format => f.write_str("the string does not match the format"),
+            ParseError::Inner(ref e) => write!(f, "failed to parse the inner value: {}", e),
+            ParseError::Validation(ref e) => write!(f, "invalid value: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<P, V> std::error::Error for ParseError<P, V>
+where
+    P: std::error::Error + 'static,
+    V: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            ParseError::Format => None,
+            ParseError::Inner(ref e) => Some(e),
+            ParseError::Validation(ref e) => Some(e),
+        }
+    }
+}
+
+/// A validation error which holds the rejected value.
+///
+/// Validators of sized types can return this as an error to hand the rejected
+/// value back (see `#[opaque_typedef(validation(rejected))]`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rejected<T, E> {
+    /// Rejected value.
+    value: T,
+    /// Validation error.
+    error: E,
+}
+
+impl<T, E> Rejected<T, E> {
+    /// Creates a new error.
+    pub fn new(value: T, error: E) -> Self {
+        Self { value, error }
+    }
+
+    /// Returns the reference to the rejected value.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns the reference to the validation error.
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    /// Takes and returns the rejected value.
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
+    /// Takes and returns the validation error.
+    pub fn into_error(self) -> E {
+        self.error
+    }
+
+    /// Takes and returns the rejected value and the validation error.
+    pub fn into_parts(self) -> (T, E) {
+        (self.value, self.error)
+    }
+}
+
+impl<T, E: core::fmt::Display> core::fmt::Display for Rejected<T, E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, E> std::error::Error for Rejected<T, E>
+where
+    T: core::fmt::Debug,
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
 /// Common functions for opaque typedef-ed sized types.
 pub trait OpaqueTypedef: Sized {
     /// Inner type.
     type Inner;
     /// Validation error type.
-    type Error: ::std::fmt::Debug;
+    type Error: core::fmt::Debug;
 
     /// Creates a new value from the inner value without validation.
     unsafe fn from_inner_unchecked(inner: Self::Inner) -> Self;
     /// Tries to create a new value from the inner value with validation.
     fn try_from_inner(inner: Self::Inner) -> Result<Self, Self::Error>;
+    /// Tries to create a new value from the inner value with validation, and
+    /// returns the given inner value back if the validation failed.
+    ///
+    /// The default implementation validates a clone of the inner value.
+    /// Generated impls override this and don't clone the value if the
+    /// validator can hand the rejected value back and the type has no
+    /// normalizer.
+    fn try_from_inner_or_return(inner: Self::Inner) -> Result<Self, (Self::Inner, Self::Error)>
+    where
+        Self::Inner: Clone,
+    {
+        match Self::try_from_inner(inner.clone()) {
+            Ok(v) => Ok(v),
+            Err(e) => Err((inner, e)),
+        }
+    }
+    /// Validates the inner value without taking the ownership.
+    ///
+    /// The default implementation validates a clone of the inner value.
+    /// Generated impls override this if the value can be checked by
+    /// reference.
+    fn validate(inner: &Self::Inner) -> Result<(), Self::Error>
+    where
+        Self::Inner: Clone,
+    {
+        Self::try_from_inner(inner.clone()).map(|_| ())
+    }
     /// Creates a new value from the inner value with validation.
     ///
     /// # Panics
@@ -37,7 +190,7 @@ pub trait OpaqueTypedefUnsized {
     /// Inner type.
     type Inner: ?Sized;
     /// Validation error type.
-    type Error: ::std::fmt::Debug;
+    type Error: core::fmt::Debug;
 
     /// Creates a reference from the inner reference without validation.
     unsafe fn from_inner_unchecked(inner: &Self::Inner) -> &Self;
@@ -46,6 +199,10 @@ pub trait OpaqueTypedefUnsized {
     unsafe fn from_inner_unchecked_mut(inner: &mut Self::Inner) -> &mut Self;
     /// Tries to create a reference from the inner reference with validation.
     fn try_from_inner(inner: &Self::Inner) -> Result<&Self, Self::Error>;
+    /// Validates the inner value.
+    fn validate(inner: &Self::Inner) -> Result<(), Self::Error> {
+        Self::try_from_inner(inner).map(|_| ())
+    }
     /// Creates a reference from the inner reference with validation.
     ///
     /// # Panics
@@ -71,3 +228,47 @@ pub trait OpaqueTypedefUnsized {
     /// Returns the inner mutable reference.
     unsafe fn as_inner_mut(&mut self) -> &mut Self::Inner;
 }
+
+/// Opaque typedef-ed sized types whose validation errors implement
+/// `std::error::Error`.
+///
+/// This is automatically implemented, and useful for generic codes to
+/// convert validation errors into `Box<dyn std::error::Error>` by `?`.
+#[cfg(feature = "std")]
+pub trait OpaqueTypedefStdError:
+    OpaqueTypedef<Error = <Self as OpaqueTypedefStdError>::StdError>
+{
+    /// Validation error type.
+    type StdError: std::error::Error + 'static;
+}
+
+#[cfg(feature = "std")]
+impl<T> OpaqueTypedefStdError for T
+where
+    T: OpaqueTypedef,
+    T::Error: std::error::Error + 'static,
+{
+    type StdError = T::Error;
+}
+
+/// Opaque typedef-ed unsized types whose validation errors implement
+/// `std::error::Error`.
+///
+/// This is automatically implemented, and useful for generic codes to
+/// convert validation errors into `Box<dyn std::error::Error>` by `?`.
+#[cfg(feature = "std")]
+pub trait OpaqueTypedefUnsizedStdError:
+    OpaqueTypedefUnsized<Error = <Self as OpaqueTypedefUnsizedStdError>::StdError>
+{
+    /// Validation error type.
+    type StdError: std::error::Error + 'static;
+}
+
+#[cfg(feature = "std")]
+impl<T> OpaqueTypedefUnsizedStdError for T
+where
+    T: OpaqueTypedefUnsized + ?Sized,
+    T::Error: std::error::Error + 'static,
+{
+    type StdError = T::Error;
+}
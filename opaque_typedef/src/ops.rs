@@ -0,0 +1,323 @@
+//! Fallible, saturating, and wrapping operator traits.
+//!
+//! Fallible traits (such as `TryAdd`) are implemented by
+//! `opaque_typedef_macros` for types with
+//! `#[opaque_typedef(ops(on_invalid = "checked"))]`, instead of operator
+//! traits in `core::ops`.
+//!
+//! Saturating traits (such as `SaturatingAdd`) and wrapping traits (such as
+//! `WrappingAdd`) are used to operate on the inner values of types with
+//! `#[opaque_typedef(ops(on_invalid = "saturate"))]` and
+//! `#[opaque_typedef(ops(on_invalid = "wrap"))]`, so that the operation on
+//! the inner values never overflows.
+//! They are implemented for primitive integers (and their references), and
+//! for floating point numbers as the regular operations (which never
+//! overflow).
+
+macro_rules! define_try_binop {
+    ($(#[$meta:meta])* $trait_:ident, $method:ident, $op:expr) => {
+        $(#[$meta])*
+        pub trait $trait_<Rhs = Self> {
+            /// The resulting type.
+            type Output;
+            /// The error type.
+            type Error;
+
+            #[doc = "Tries to perform the `"]
+            #[doc = $op]
+            #[doc = "` operation."]
+            fn $method(self, rhs: Rhs) -> Result<Self::Output, Self::Error>;
+        }
+    };
+}
+
+macro_rules! define_try_unop {
+    ($(#[$meta:meta])* $trait_:ident, $method:ident, $op:expr) => {
+        $(#[$meta])*
+        pub trait $trait_ {
+            /// The resulting type.
+            type Output;
+            /// The error type.
+            type Error;
+
+            #[doc = "Tries to perform the unary `"]
+            #[doc = $op]
+            #[doc = "` operation."]
+            fn $method(self) -> Result<Self::Output, Self::Error>;
+        }
+    };
+}
+
+define_try_binop!(
+    /// Fallible version of `Add`.
+    TryAdd, try_add, "+"
+);
+define_try_binop!(
+    /// Fallible version of `BitAnd`.
+    TryBitAnd, try_bitand, "&"
+);
+define_try_binop!(
+    /// Fallible version of `BitOr`.
+    TryBitOr, try_bitor, "|"
+);
+define_try_binop!(
+    /// Fallible version of `BitXor`.
+    TryBitXor, try_bitxor, "^"
+);
+define_try_binop!(
+    /// Fallible version of `Div`.
+    TryDiv, try_div, "/"
+);
+define_try_binop!(
+    /// Fallible version of `Mul`.
+    TryMul, try_mul, "*"
+);
+define_try_binop!(
+    /// Fallible version of `Rem`.
+    TryRem, try_rem, "%"
+);
+define_try_binop!(
+    /// Fallible version of `Shl`.
+    TryShl, try_shl, "<<"
+);
+define_try_binop!(
+    /// Fallible version of `Shr`.
+    TryShr, try_shr, ">>"
+);
+define_try_binop!(
+    /// Fallible version of `Sub`.
+    TrySub, try_sub, "-"
+);
+define_try_unop!(
+    /// Fallible version of `Neg`.
+    TryNeg, try_neg, "-"
+);
+define_try_unop!(
+    /// Fallible version of `Not`.
+    TryNot, try_not, "!"
+);
+
+macro_rules! define_overflow_binop {
+    ($(#[$meta:meta])* $trait_:ident, $method:ident, $op:expr) => {
+        $(#[$meta])*
+        pub trait $trait_<Rhs = Self> {
+            /// The resulting type.
+            type Output;
+
+            #[doc = "Performs the `"]
+            #[doc = $op]
+            #[doc = "` operation without overflow."]
+            fn $method(self, rhs: Rhs) -> Self::Output;
+        }
+    };
+}
+
+macro_rules! define_overflow_unop {
+    ($(#[$meta:meta])* $trait_:ident, $method:ident, $op:expr) => {
+        $(#[$meta])*
+        pub trait $trait_ {
+            /// The resulting type.
+            type Output;
+
+            #[doc = "Performs the unary `"]
+            #[doc = $op]
+            #[doc = "` operation without overflow."]
+            fn $method(self) -> Self::Output;
+        }
+    };
+}
+
+define_overflow_binop!(
+    /// Saturating version of `Add`.
+    SaturatingAdd, saturating_add, "+"
+);
+define_overflow_binop!(
+    /// Saturating version of `Div`.
+    ///
+    /// The result of `MIN / -1` saturates to `MAX`.
+    /// Note that division by zero still panics.
+    SaturatingDiv, saturating_div, "/"
+);
+define_overflow_binop!(
+    /// Saturating version of `Mul`.
+    SaturatingMul, saturating_mul, "*"
+);
+define_overflow_binop!(
+    /// Saturating version of `Sub`.
+    SaturatingSub, saturating_sub, "-"
+);
+define_overflow_unop!(
+    /// Saturating version of `Neg`.
+    SaturatingNeg, saturating_neg, "-"
+);
+define_overflow_binop!(
+    /// Wrapping version of `Add`.
+    WrappingAdd, wrapping_add, "+"
+);
+define_overflow_binop!(
+    /// Wrapping version of `Div`.
+    ///
+    /// Note that division by zero still panics.
+    WrappingDiv, wrapping_div, "/"
+);
+define_overflow_binop!(
+    /// Wrapping version of `Mul`.
+    WrappingMul, wrapping_mul, "*"
+);
+define_overflow_binop!(
+    /// Wrapping version of `Rem`.
+    ///
+    /// Note that division by zero still panics.
+    WrappingRem, wrapping_rem, "%"
+);
+define_overflow_binop!(
+    /// Wrapping version of `Shl`.
+    ///
+    /// The shift amount is masked to the bit width of the type.
+    WrappingShl, wrapping_shl, "<<"
+);
+define_overflow_binop!(
+    /// Wrapping version of `Shr`.
+    ///
+    /// The shift amount is masked to the bit width of the type.
+    WrappingShr, wrapping_shr, ">>"
+);
+define_overflow_binop!(
+    /// Wrapping version of `Sub`.
+    WrappingSub, wrapping_sub, "-"
+);
+define_overflow_unop!(
+    /// Wrapping version of `Neg`.
+    WrappingNeg, wrapping_neg, "-"
+);
+
+/// Implements the binary operator trait for the value and its references.
+macro_rules! impl_overflow_binop {
+    ($ty:ty, $trait_:ident, $method:ident, |$lhs:ident, $rhs:ident| $body:expr) => {
+        impl $trait_ for $ty {
+            type Output = $ty;
+
+            #[inline]
+            fn $method(self, rhs: $ty) -> $ty {
+                let ($lhs, $rhs) = (self, rhs);
+                $body
+            }
+        }
+
+        impl<'a> $trait_<&'a $ty> for $ty {
+            type Output = $ty;
+
+            #[inline]
+            fn $method(self, rhs: &'a $ty) -> $ty {
+                $trait_::$method(self, *rhs)
+            }
+        }
+
+        impl<'a> $trait_<$ty> for &'a $ty {
+            type Output = $ty;
+
+            #[inline]
+            fn $method(self, rhs: $ty) -> $ty {
+                $trait_::$method(*self, rhs)
+            }
+        }
+
+        impl<'a, 'b> $trait_<&'b $ty> for &'a $ty {
+            type Output = $ty;
+
+            #[inline]
+            fn $method(self, rhs: &'b $ty) -> $ty {
+                $trait_::$method(*self, *rhs)
+            }
+        }
+    };
+}
+
+/// Implements the unary operator trait for the value and its reference.
+macro_rules! impl_overflow_unop {
+    ($ty:ty, $trait_:ident, $method:ident, |$v:ident| $body:expr) => {
+        impl $trait_ for $ty {
+            type Output = $ty;
+
+            #[inline]
+            fn $method(self) -> $ty {
+                let $v = self;
+                $body
+            }
+        }
+
+        impl<'a> $trait_ for &'a $ty {
+            type Output = $ty;
+
+            #[inline]
+            fn $method(self) -> $ty {
+                $trait_::$method(*self)
+            }
+        }
+    };
+}
+
+macro_rules! impl_overflow_ops_int {
+    ($($ty:ty),*) => {
+        $(
+            impl_overflow_binop!($ty, SaturatingAdd, saturating_add, |l, r| l.saturating_add(r));
+            impl_overflow_binop!($ty, SaturatingMul, saturating_mul, |l, r| l.saturating_mul(r));
+            impl_overflow_binop!($ty, SaturatingSub, saturating_sub, |l, r| l.saturating_sub(r));
+            impl_overflow_binop!($ty, WrappingAdd, wrapping_add, |l, r| l.wrapping_add(r));
+            impl_overflow_binop!($ty, WrappingDiv, wrapping_div, |l, r| l.wrapping_div(r));
+            impl_overflow_binop!($ty, WrappingMul, wrapping_mul, |l, r| l.wrapping_mul(r));
+            impl_overflow_binop!($ty, WrappingRem, wrapping_rem, |l, r| l.wrapping_rem(r));
+            impl_overflow_binop!($ty, WrappingShl, wrapping_shl, |l, r| l.wrapping_shl(r as u32));
+            impl_overflow_binop!($ty, WrappingShr, wrapping_shr, |l, r| l.wrapping_shr(r as u32));
+            impl_overflow_binop!($ty, WrappingSub, wrapping_sub, |l, r| l.wrapping_sub(r));
+        )*
+    };
+}
+
+macro_rules! impl_overflow_ops_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl_overflow_binop!($ty, SaturatingDiv, saturating_div, |l, r| l / r);
+        )*
+    };
+}
+
+macro_rules! impl_overflow_ops_signed {
+    ($($ty:ty),*) => {
+        $(
+            impl_overflow_binop!($ty, SaturatingDiv, saturating_div, |l, r| {
+                // `MIN / -1` is the only overflowing case.
+                if r == -1 {
+                    (0 as $ty).saturating_sub(l)
+                } else {
+                    l / r
+                }
+            });
+            impl_overflow_unop!($ty, SaturatingNeg, saturating_neg, |v| (0 as $ty).saturating_sub(v));
+            impl_overflow_unop!($ty, WrappingNeg, wrapping_neg, |v| v.wrapping_neg());
+        )*
+    };
+}
+
+macro_rules! impl_overflow_ops_float {
+    ($($ty:ty),*) => {
+        $(
+            impl_overflow_binop!($ty, SaturatingAdd, saturating_add, |l, r| l + r);
+            impl_overflow_binop!($ty, SaturatingDiv, saturating_div, |l, r| l / r);
+            impl_overflow_binop!($ty, SaturatingMul, saturating_mul, |l, r| l * r);
+            impl_overflow_binop!($ty, SaturatingSub, saturating_sub, |l, r| l - r);
+            impl_overflow_unop!($ty, SaturatingNeg, saturating_neg, |v| -v);
+            impl_overflow_binop!($ty, WrappingAdd, wrapping_add, |l, r| l + r);
+            impl_overflow_binop!($ty, WrappingDiv, wrapping_div, |l, r| l / r);
+            impl_overflow_binop!($ty, WrappingMul, wrapping_mul, |l, r| l * r);
+            impl_overflow_binop!($ty, WrappingRem, wrapping_rem, |l, r| l % r);
+            impl_overflow_binop!($ty, WrappingSub, wrapping_sub, |l, r| l - r);
+            impl_overflow_unop!($ty, WrappingNeg, wrapping_neg, |v| -v);
+        )*
+    };
+}
+
+impl_overflow_ops_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_overflow_ops_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_overflow_ops_signed!(i8, i16, i32, i64, i128, isize);
+impl_overflow_ops_float!(f32, f64);
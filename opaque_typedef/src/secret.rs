@@ -0,0 +1,57 @@
+//! Support for secret-bearing types.
+//!
+//! These items are used by `opaque_typedef_macros` for types with
+//! `#[opaque_typedef(secret)]`.
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::string::String;
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::vec::Vec;
+
+/// Types whose contents can be overwritten with zeroes.
+///
+/// The writes are volatile, so that they are not optimized out even if the
+/// value is never read again.
+pub trait Zeroize {
+    /// Overwrites the contents with zeroes.
+    fn zeroize(&mut self);
+}
+
+macro_rules! impl_zeroize_for_int {
+    ($($ty:ty),*) => {
+        $(
+            impl Zeroize for $ty {
+                fn zeroize(&mut self) {
+                    unsafe { core::ptr::write_volatile(self, 0) };
+                    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+                }
+            }
+        )*
+    };
+}
+
+impl_zeroize_for_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Overwrites the whole buffer (including the spare capacity) and clears the
+/// vector.
+#[cfg(feature = "alloc")]
+impl Zeroize for Vec<u8> {
+    fn zeroize(&mut self) {
+        let ptr = self.as_mut_ptr();
+        for i in 0..self.capacity() {
+            unsafe { core::ptr::write_volatile(ptr.add(i), 0) };
+        }
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+        self.clear();
+    }
+}
+
+/// Overwrites the whole buffer (including the spare capacity) and clears the
+/// string.
+#[cfg(feature = "alloc")]
+impl Zeroize for String {
+    fn zeroize(&mut self) {
+        // Zeroes are valid UTF-8, and the string is cleared after all.
+        unsafe { self.as_mut_vec() }.zeroize();
+    }
+}
@@ -0,0 +1,204 @@
+//! Composable validators.
+//!
+//! Validators implementing [`Validator`] can be used by
+//! `opaque_typedef_macros` for types with
+//! `#[opaque_typedef(validation(with = ..))]`, and can be combined by
+//! methods of [`ValidatorExt`].
+//!
+//! [`Validator`]: trait.Validator.html
+//! [`ValidatorExt`]: trait.ValidatorExt.html
+
+/// Validator of values of type `T`.
+pub trait Validator<T: ?Sized> {
+    /// Validation error type.
+    type Error;
+
+    /// Validates the value.
+    fn validate(&self, value: &T) -> Result<(), Self::Error>;
+}
+
+impl<T: ?Sized, V: Validator<T> + ?Sized> Validator<T> for &V {
+    type Error = V::Error;
+
+    fn validate(&self, value: &T) -> Result<(), Self::Error> {
+        (**self).validate(value)
+    }
+}
+
+/// Combinators of validators.
+///
+/// This is not implemented for all types, so that the methods don't conflict
+/// with methods of other traits (such as `std::ops::Not::not`).
+/// Implement this for your validator types to combine them, for example
+/// `impl ValidatorExt for NonEmpty {}`.
+///
+/// The combined validators implement `Validator<T>` if the given validators
+/// implement it.
+pub trait ValidatorExt: Sized {
+    /// Returns a validator which accepts values accepted by both validators.
+    ///
+    /// `other` is not used if `self` rejected the value.
+    fn and<V>(self, other: V) -> And<Self, V> {
+        And(self, other)
+    }
+
+    /// Returns a validator which accepts values accepted by any of the
+    /// validators.
+    ///
+    /// `other` is not used if `self` accepted the value.
+    fn or<V>(self, other: V) -> Or<Self, V> {
+        Or(self, other)
+    }
+
+    /// Returns a validator which accepts values rejected by `self`.
+    fn not(self) -> Not<Self> {
+        Not(self)
+    }
+
+    /// Returns a validator which converts the error by the given function.
+    fn map_err<F>(self, f: F) -> MapErr<Self, F> {
+        MapErr { validator: self, f }
+    }
+}
+
+impl<V: ValidatorExt> ValidatorExt for &V {}
+
+impl<A, B> ValidatorExt for And<A, B> {}
+
+impl<A, B> ValidatorExt for Or<A, B> {}
+
+impl<V> ValidatorExt for Not<V> {}
+
+impl<V, F> ValidatorExt for MapErr<V, F> {}
+
+/// Validator created by [`ValidatorExt::and`].
+///
+/// [`ValidatorExt::and`]: trait.ValidatorExt.html#method.and
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct And<A, B>(A, B);
+
+impl<T: ?Sized, A: Validator<T>, B: Validator<T>> Validator<T> for And<A, B> {
+    type Error = Either<A::Error, B::Error>;
+
+    fn validate(&self, value: &T) -> Result<(), Self::Error> {
+        self.0.validate(value).map_err(Either::Left)?;
+        self.1.validate(value).map_err(Either::Right)
+    }
+}
+
+/// Validator created by [`ValidatorExt::or`].
+///
+/// The error is the one returned by the second validator.
+///
+/// [`ValidatorExt::or`]: trait.ValidatorExt.html#method.or
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Or<A, B>(A, B);
+
+impl<T: ?Sized, A: Validator<T>, B: Validator<T>> Validator<T> for Or<A, B> {
+    type Error = B::Error;
+
+    fn validate(&self, value: &T) -> Result<(), Self::Error> {
+        match self.0.validate(value) {
+            Ok(()) => Ok(()),
+            Err(_) => self.1.validate(value),
+        }
+    }
+}
+
+/// Validator created by [`ValidatorExt::not`].
+///
+/// [`ValidatorExt::not`]: trait.ValidatorExt.html#method.not
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Not<V>(V);
+
+impl<T: ?Sized, V: Validator<T>> Validator<T> for Not<V> {
+    type Error = NotError;
+
+    fn validate(&self, value: &T) -> Result<(), Self::Error> {
+        match self.0.validate(value) {
+            Ok(()) => Err(NotError),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+/// Validator created by [`ValidatorExt::map_err`].
+///
+/// [`ValidatorExt::map_err`]: trait.ValidatorExt.html#method.map_err
+#[derive(Clone, Copy)]
+pub struct MapErr<V, F> {
+    /// Validator.
+    validator: V,
+    /// Error converter.
+    f: F,
+}
+
+impl<V: core::fmt::Debug, F> core::fmt::Debug for MapErr<V, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("MapErr")
+            .field("validator", &self.validator)
+            .finish()
+    }
+}
+
+impl<T: ?Sized, V: Validator<T>, F: Fn(V::Error) -> E, E> Validator<T> for MapErr<V, F> {
+    type Error = E;
+
+    fn validate(&self, value: &T) -> Result<(), Self::Error> {
+        self.validator.validate(value).map_err(&self.f)
+    }
+}
+
+/// An error of one of the two validators.
+///
+/// This is used as an error type of [`And`].
+///
+/// [`And`]: struct.And.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Either<L, R> {
+    /// An error of the first validator.
+    Left(L),
+    /// An error of the second validator.
+    Right(R),
+}
+
+impl<L: core::fmt::Display, R: core::fmt::Display> core::fmt::Display for Either<L, R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match *self {
+            Either::Left(ref e) => e.fmt(f),
+            Either::Right(ref e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<L, R> std::error::Error for Either<L, R>
+where
+    L: std::error::Error + 'static,
+    R: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            Either::Left(ref e) => e.source(),
+            Either::Right(ref e) => e.source(),
+        }
+    }
+}
+
+/// An error type that indicates the value is accepted by the negated
+/// validator.
+///
+/// This is used as an error type of [`Not`].
+///
+/// [`Not`]: struct.Not.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NotError;
+
+impl core::fmt::Display for NotError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("the value is accepted by the negated validator")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NotError {}
@@ -2,14 +2,17 @@
 
 use proc_macro2::TokenStream;
 use quote::ToTokens;
+use syn;
 
+use paths;
 use type_props::TypeProps;
 
 use super::deref::{gen_deref_expr, gen_deref_mut_expr};
 use super::Derive;
 
 /// Generates an impl for the target.
-pub fn gen_impl(target: Derive, props: &TypeProps) -> TokenStream {
+pub fn gen_impl(target: Derive, props: &TypeProps) -> syn::Result<TokenStream> {
+    let core = paths::core();
     let ty_outer = props.ty_outer;
     let impl_generics = &props.impl_generics;
     let type_generics = &props.type_generics;
@@ -23,11 +26,7 @@ pub fn gen_impl(target: Derive, props: &TypeProps) -> TokenStream {
     match target {
         Derive::AsMutDeref | Derive::AsMutInner | Derive::AsMutSelf => {
             if !props.is_mut_ref_allowed {
-                panic!(
-                    "`#[opaque_typedef(derive({}))]` requires \
-                     `#[opaque_typedef(allow_mut_ref)]`, but not specified",
-                    target.as_ref()
-                );
+                return Err(target.error_mut_ref_not_allowed(props));
             }
         }
         _ => {}
@@ -42,11 +41,11 @@ pub fn gen_impl(target: Derive, props: &TypeProps) -> TokenStream {
         _ => unreachable!("Should never happen"),
     };
 
-    match target {
+    let tokens = match target {
         Derive::AsMutDeref | Derive::AsMutInner | Derive::AsMutSelf => {
             quote! {
                 impl #impl_generics
-                    ::std::convert::AsMut<#ty_conv_target> for #ty_outer #type_generics
+                    #core::convert::AsMut<#ty_conv_target> for #ty_outer #type_generics
                 #where_clause
                 {
                     fn as_mut(&mut self) -> &mut #ty_conv_target {
@@ -58,7 +57,7 @@ pub fn gen_impl(target: Derive, props: &TypeProps) -> TokenStream {
         Derive::AsRefDeref | Derive::AsRefInner | Derive::AsRefSelf => {
             quote! {
                 impl #impl_generics
-                    ::std::convert::AsRef<#ty_conv_target> for #ty_outer #type_generics
+                    #core::convert::AsRef<#ty_conv_target> for #ty_outer #type_generics
                 #where_clause
                 {
                     fn as_ref(&self) -> &#ty_conv_target {
@@ -68,5 +67,6 @@ pub fn gen_impl(target: Derive, props: &TypeProps) -> TokenStream {
             }
         }
         _ => unreachable!("Should never happen"),
-    }
+    };
+    Ok(tokens)
 }
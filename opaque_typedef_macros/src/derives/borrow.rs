@@ -0,0 +1,210 @@
+//! Impl generators for owned / borrowed pairs of opaque typedefs.
+
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn;
+use syn::spanned::Spanned;
+
+use paths;
+use type_props::{Sizedness, TypeProps};
+use utils::extend_generics;
+
+use super::Derive;
+
+/// Generates impls to link the owned type and the borrowed type.
+///
+/// The impls are generated only from the unsized side
+/// (`#[opaque_typedef(owned = ..)]`), so that they are not generated twice
+/// when both sides are linked. The sized side
+/// (`#[opaque_typedef(borrowed = ..)]`) only checks that the borrowed type is
+/// linked to the owned type.
+///
+/// The generated conversions don't validate the value again, because the
+/// source value is already validated.
+pub fn gen_impls_borrow_pair(props: &TypeProps) -> syn::Result<TokenStream> {
+    let ty_counterpart = match props.counterpart {
+        Some(ref ty) => ty,
+        None => return Ok(quote!()),
+    };
+    let ty_outer = props.ty_outer;
+    let type_generics = &props.type_generics;
+    let ty_self = quote!(#ty_outer #type_generics);
+    let core = paths::core();
+    let alloc = paths::alloc(
+        ty_counterpart.span(),
+        format_args!(
+            "`#[opaque_typedef({} = ..)]`",
+            match props.inner_sizedness {
+                Sizedness::Sized => "borrowed",
+                Sizedness::Unsized => "owned",
+            }
+        ),
+    )?;
+    let (ty_owned, ty_borrowed) = match props.inner_sizedness {
+        Sizedness::Sized => {
+            if props.derives.contains(&Derive::Deref) {
+                return Err(syn::Error::new(
+                    props.derive_span(Derive::Deref),
+                    "`#[opaque_typedef(derive(Deref))]` cannot be used with \
+                     `#[opaque_typedef(borrowed = ..)]`, because `Deref` to the borrowed type \
+                     is automatically implemented",
+                ));
+            }
+            return Ok(gen_borrowed_assertion(props, ty_counterpart));
+        }
+        Sizedness::Unsized => (ty_counterpart.into_token_stream(), ty_self),
+    };
+    let helper_owned = quote!(::opaque_typedef::OpaqueTypedef);
+    let helper_borrowed = quote!(::opaque_typedef::OpaqueTypedefUnsized);
+    let ty_owned_inner = quote!(<#ty_owned as #helper_owned>::Inner);
+    let ty_borrowed_inner = quote!(<#ty_borrowed as #helper_borrowed>::Inner);
+
+    let gen_generics = |num_new_lifetimes: usize, pred: &str| {
+        let extra_preds = if props.has_type_params() {
+            let pred = syn::parse_str::<syn::WherePredicate>(pred)
+                .expect("Failed to generate `WherePredicate`");
+            vec![pred]
+        } else {
+            Vec::new()
+        };
+        extend_generics(
+            props.generics_with_helper_trait_preds(),
+            num_new_lifetimes,
+            &extra_preds,
+        )
+    };
+    let pred_borrow = format!(
+        "{}: {}::borrow::Borrow<{}>",
+        ty_owned_inner, core, ty_borrowed_inner
+    );
+    let pred_to_owned = format!(
+        "{}: {}::borrow::ToOwned<Owned = {}>",
+        ty_borrowed_inner, alloc, ty_owned_inner
+    );
+    let pred_box = format!(
+        "{}::boxed::Box<{}>: {}::convert::From<{}>",
+        alloc, ty_borrowed_inner, core, ty_owned_inner
+    );
+
+    let impl_borrow = {
+        let (generics, _) = gen_generics(0, &pred_borrow);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        quote! {
+            impl #impl_generics #core::borrow::Borrow<#ty_borrowed> for #ty_owned
+            #where_clause
+            {
+                fn borrow(&self) -> &#ty_borrowed {
+                    let __inner = <#ty_owned_inner as #core::borrow::Borrow<#ty_borrowed_inner>>
+                        ::borrow(<#ty_owned as #helper_owned>::as_inner(self));
+                    unsafe { <#ty_borrowed as #helper_borrowed>::from_inner_unchecked(__inner) }
+                }
+            }
+
+            impl #impl_generics #core::convert::AsRef<#ty_borrowed> for #ty_owned
+            #where_clause
+            {
+                fn as_ref(&self) -> &#ty_borrowed {
+                    <#ty_owned as #core::borrow::Borrow<#ty_borrowed>>::borrow(self)
+                }
+            }
+
+            impl #impl_generics #core::ops::Deref for #ty_owned
+            #where_clause
+            {
+                type Target = #ty_borrowed;
+                fn deref(&self) -> &Self::Target {
+                    <#ty_owned as #core::borrow::Borrow<#ty_borrowed>>::borrow(self)
+                }
+            }
+        }
+    };
+    let impl_to_owned = {
+        let (generics, _) = gen_generics(0, &pred_to_owned);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        quote! {
+            impl #impl_generics #alloc::borrow::ToOwned for #ty_borrowed
+            #where_clause
+            {
+                type Owned = #ty_owned;
+                fn to_owned(&self) -> Self::Owned {
+                    let __inner = <#ty_borrowed_inner as #alloc::borrow::ToOwned>::to_owned(
+                        <#ty_borrowed as #helper_borrowed>::as_inner(self)
+                    );
+                    unsafe { <#ty_owned as #helper_owned>::from_inner_unchecked(__inner) }
+                }
+            }
+        }
+    };
+    let impl_from_borrowed = {
+        let (generics, new_lifetimes) = gen_generics(1, &pred_to_owned);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        let new_lt = &new_lifetimes[0];
+        quote! {
+            impl #impl_generics #core::convert::From<&#new_lt #ty_borrowed> for #ty_owned
+            #where_clause
+            {
+                fn from(__other: &#new_lt #ty_borrowed) -> Self {
+                    <#ty_borrowed as #alloc::borrow::ToOwned>::to_owned(__other)
+                }
+            }
+        }
+    };
+    let impl_into_box = {
+        let (generics, _) = gen_generics(0, &pred_box);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        quote! {
+            impl #impl_generics #core::convert::From<#ty_owned> for #alloc::boxed::Box<#ty_borrowed>
+            #where_clause
+            {
+                fn from(__other: #ty_owned) -> Self {
+                    let inner = <#ty_owned as #helper_owned>::into_inner(__other);
+                    let boxed_inner = #alloc::boxed::Box::<#ty_borrowed_inner>::from(inner);
+                    let raw = #alloc::boxed::Box::into_raw(boxed_inner) as *mut #ty_borrowed;
+                    unsafe { #alloc::boxed::Box::from_raw(raw) }
+                }
+            }
+        }
+    };
+
+    Ok(quote! {
+        #impl_borrow
+        #impl_to_owned
+        #impl_from_borrowed
+        #impl_into_box
+    })
+}
+
+/// Generates the assertion that the borrowed type is linked to the owned type
+/// by `#[opaque_typedef(owned = ..)]`.
+fn gen_borrowed_assertion(props: &TypeProps, ty_borrowed: &syn::Type) -> TokenStream {
+    let core = paths::core();
+    let ty_outer = props.ty_outer;
+    let generics = props.generics_with_helper_trait_preds();
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let type_generics = &props.type_generics;
+    let helper_owned = quote!(::opaque_typedef::OpaqueTypedef);
+    let helper_borrowed = quote!(::opaque_typedef::OpaqueTypedefUnsized);
+    let ty_owned_inner = quote!(<Self as #helper_owned>::Inner);
+    let ty_borrowed_inner = quote!(<#ty_borrowed as #helper_borrowed>::Inner);
+    let extra_where_clause = if props.has_type_params() {
+        quote!(where #ty_owned_inner: #core::borrow::Borrow<#ty_borrowed_inner>)
+    } else {
+        quote!()
+    };
+    let borrow = quote_spanned! {ty_borrowed.span()=>
+        <Self as #core::borrow::Borrow<#ty_borrowed>>::borrow(self)
+    };
+    quote! {
+        impl #impl_generics #ty_outer #type_generics
+        #where_clause
+        {
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            fn __opaque_typedef_assert_borrowed(&self) -> &#ty_borrowed
+            #extra_where_clause
+            {
+                #borrow
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
-//! Impl generators for `std::cmp::Partial{Eq,Ord}` traits.
+//! Impl generators for `std::cmp::{PartialEq, Eq, PartialOrd, Ord}` and
+//! `std::hash::Hash` traits.
 
 use std::borrow::Cow;
 
@@ -6,12 +7,41 @@ use proc_macro2::TokenStream;
 use quote::ToTokens;
 use syn;
 
+use paths;
 use type_props::{CmpSpec, Sizedness, TypeProps};
 use utils::extend_generics;
 
 use super::Derive;
 
-pub fn gen_impl_ord(props: &TypeProps) -> TokenStream {
+/// Generates an impl for `Eq`.
+pub fn gen_impl_eq(props: &TypeProps) -> TokenStream {
+    let core = paths::core();
+    let ty_outer = &props.ty_outer;
+    let type_generics = &props.type_generics;
+    let ty_inner = props.field_inner.ty();
+    let extra_preds = if props.has_type_params() && props.cmp_spec.uses_inner_traits() {
+        let pred = syn::parse_str::<syn::WherePredicate>(&format!(
+            "{}: {}::cmp::Eq",
+            ty_inner.into_token_stream(),
+            core
+        ))
+        .expect("Failed to generate `WherePredicate`");
+        vec![pred]
+    } else {
+        Vec::new()
+    };
+    let (generics, _) = extend_generics(Cow::Borrowed(props.generics), 0, &extra_preds);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics #core::cmp::Eq for #ty_outer #type_generics
+        #where_clause
+        {}
+    }
+}
+
+/// Generates an impl for `Ord`.
+pub fn gen_impl_ord(props: &TypeProps) -> syn::Result<TokenStream> {
+    check_ordering_allowed(Derive::Ord, props)?;
     let ty_outer = &props.ty_outer;
     let type_generics = &props.type_generics;
     let ty_outer_generic = quote!(#ty_outer #type_generics);
@@ -19,7 +49,7 @@ pub fn gen_impl_ord(props: &TypeProps) -> TokenStream {
     let self_as_inner = props.tokens_outer_expr_as_inner(quote!(self));
     let other_as_inner = props.tokens_outer_expr_as_inner(quote!(other));
 
-    CmpImplSpec {
+    Ok(CmpImplSpec {
         type_props: &props,
         generics: props.generics,
         trait_spec: CmpTraitSpec::Ord,
@@ -30,13 +60,62 @@ pub fn gen_impl_ord(props: &TypeProps) -> TokenStream {
         ty_rhs: &ty_outer_generic,
         rhs_other_as_inner: &other_as_inner,
     }
-    .gen_impl()
+    .gen_impl())
+}
+
+/// Generates an impl for `Hash`.
+///
+/// The hash function should be consistent with the `PartialEq` comparator,
+/// so the default hash function is refused if the custom `PartialEq`
+/// comparator is specified (unless `cmp(allow_default_hash)` is specified).
+pub fn gen_impl_hash(props: &TypeProps) -> syn::Result<TokenStream> {
+    let core = paths::core();
+    let ty_outer = &props.ty_outer;
+    let type_generics = &props.type_generics;
+    let ty_inner = props.field_inner.ty();
+    let self_as_inner = props.tokens_outer_expr_as_inner(quote!(self));
+    let cmp_spec = &props.cmp_spec;
+    if cmp_spec.partial_eq.is_some() && cmp_spec.hash.is_none() && !cmp_spec.allow_default_hash {
+        return Err(syn::Error::new(
+            props.derive_span(Derive::Hash),
+            "`#[opaque_typedef(derive(Hash))]` with the default hash function would be \
+             inconsistent with the custom `#[opaque_typedef(cmp(partial_eq = ..))]`; \
+             specify `#[opaque_typedef(cmp(hash = ..))]`, or \
+             `#[opaque_typedef(cmp(allow_default_hash))]` to use the default one anyway",
+        ));
+    }
+    let extra_preds =
+        if props.has_type_params() && cmp_spec.hash.is_none() && cmp_spec.uses_inner_traits() {
+            let pred = syn::parse_str::<syn::WherePredicate>(&format!(
+                "{}: {}::hash::Hash",
+                ty_inner.into_token_stream(),
+                core
+            ))
+            .expect("Failed to generate `WherePredicate`");
+            vec![pred]
+        } else {
+            Vec::new()
+        };
+    let (generics, _) = extend_generics(Cow::Borrowed(props.generics), 0, &extra_preds);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let fn_hash = cmp_spec.hash();
+    Ok(quote! {
+        impl #impl_generics #core::hash::Hash for #ty_outer #type_generics
+        #where_clause
+        {
+            fn hash<__H: #core::hash::Hasher>(&self, state: &mut __H) {
+                #fn_hash(#self_as_inner as &#ty_inner, state)
+            }
+        }
+    })
 }
 
 /// Generates an impl for the target.
-pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
+pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> syn::Result<TokenStream> {
     let trait_spec = match target {
-        Derive::PartialEqInner
+        Derive::PartialEqDeref
+        | Derive::PartialEqDerefRev
+        | Derive::PartialEqInner
         | Derive::PartialEqInnerRev
         | Derive::PartialEqInnerCow
         | Derive::PartialEqInnerCowRev
@@ -45,7 +124,9 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
         | Derive::PartialEqSelfCowRev
         | Derive::PartialEqSelfCowAndInner
         | Derive::PartialEqSelfCowAndInnerRev => CmpTraitSpec::PartialEq,
-        Derive::PartialOrdInner
+        Derive::PartialOrdDeref
+        | Derive::PartialOrdDerefRev
+        | Derive::PartialOrdInner
         | Derive::PartialOrdInnerRev
         | Derive::PartialOrdInnerCow
         | Derive::PartialOrdInnerCowRev
@@ -56,12 +137,24 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
         | Derive::PartialOrdSelfCowAndInnerRev => CmpTraitSpec::PartialOrd,
         _ => unreachable!("Should never happen"),
     };
+    if let CmpTraitSpec::PartialOrd = trait_spec {
+        check_ordering_allowed(target, props)?;
+    }
+    match target {
+        Derive::PartialEqDeref
+        | Derive::PartialEqDerefRev
+        | Derive::PartialOrdDeref
+        | Derive::PartialOrdDerefRev => {
+            return gen_impl_partial_cmp_deref(target, trait_spec, props)
+        }
+        _ => {}
+    }
     let ty_outer = &props.ty_outer;
     let type_generics = &props.type_generics;
     let ty_inner = props.field_inner.ty();
     let self_as_inner = props.tokens_outer_expr_as_inner(quote!(self));
     let other_as_inner = props.tokens_outer_expr_as_inner(quote!(other));
-    match target {
+    let tokens = match target {
         Derive::PartialEqInner | Derive::PartialOrdInner => {
             let inner_and_outer = CmpImplSpec {
                 type_props: &props,
@@ -167,6 +260,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
             }
         }
         Derive::PartialEqInnerCow | Derive::PartialOrdInnerCow => {
+            let cow = tokens_cow(target, props)?;
             let (generics, new_lts) = extend_generics(Cow::Borrowed(props.generics), 1, &[]);
             let new_lt = &new_lts[0];
             let inner_cow_and_outer = CmpImplSpec {
@@ -175,7 +269,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
                 trait_spec,
                 cmp_spec: &props.cmp_spec,
                 ty_inner,
-                ty_lhs: quote!(::std::borrow::Cow<#new_lt, #ty_inner>),
+                ty_lhs: quote!(#cow<#new_lt, #ty_inner>),
                 lhs_self_as_inner: &quote!(&*self),
                 ty_rhs: quote!(#ty_outer #type_generics),
                 rhs_other_as_inner: &other_as_inner,
@@ -190,7 +284,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
                 trait_spec,
                 cmp_spec: &props.cmp_spec,
                 ty_inner,
-                ty_lhs: quote!(::std::borrow::Cow<#new_lt0, #ty_inner>),
+                ty_lhs: quote!(#cow<#new_lt0, #ty_inner>),
                 lhs_self_as_inner: &quote!(&*self),
                 ty_rhs: quote!(&#new_lt1 #ty_outer #type_generics),
                 rhs_other_as_inner: &props.tokens_outer_expr_as_inner(quote!(*other)),
@@ -202,6 +296,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
             }
         }
         Derive::PartialEqInnerCowRev | Derive::PartialOrdInnerCowRev => {
+            let cow = tokens_cow(target, props)?;
             let (generics, new_lts) = extend_generics(Cow::Borrowed(props.generics), 1, &[]);
             let new_lt = &new_lts[0];
             let inner_cow_and_outer_rev = CmpImplSpec {
@@ -212,7 +307,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
                 ty_inner,
                 ty_lhs: quote!(#ty_outer #type_generics),
                 lhs_self_as_inner: &self_as_inner,
-                ty_rhs: quote!(::std::borrow::Cow<#new_lt, #ty_inner>),
+                ty_rhs: quote!(#cow<#new_lt, #ty_inner>),
                 rhs_other_as_inner: &quote!(&*other),
             }
             .gen_impl();
@@ -227,7 +322,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
                 ty_inner,
                 ty_lhs: quote!(&#new_lt0 #ty_outer #type_generics),
                 lhs_self_as_inner: &props.tokens_outer_expr_as_inner(quote!(*self)),
-                ty_rhs: quote!(::std::borrow::Cow<#new_lt1, #ty_inner>),
+                ty_rhs: quote!(#cow<#new_lt1, #ty_inner>),
                 rhs_other_as_inner: &quote!(&*other),
             }
             .gen_impl();
@@ -254,6 +349,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
             }
         }
         Derive::PartialEqSelfCow | Derive::PartialOrdSelfCow => {
+            let cow = tokens_cow(target, props)?;
             let (generics, new_lts) = extend_generics(Cow::Borrowed(props.generics), 1, &[]);
             let new_lt = &new_lts[0];
             let outer_cow_and_outer = CmpImplSpec {
@@ -262,7 +358,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
                 trait_spec,
                 cmp_spec: &props.cmp_spec,
                 ty_inner,
-                ty_lhs: quote!(::std::borrow::Cow<#new_lt, #ty_outer #type_generics>),
+                ty_lhs: quote!(#cow<#new_lt, #ty_outer #type_generics>),
                 lhs_self_as_inner: &props.tokens_outer_expr_as_inner(quote!(&*self)),
                 ty_rhs: quote!(#ty_outer #type_generics),
                 rhs_other_as_inner: &other_as_inner,
@@ -277,7 +373,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
                 trait_spec,
                 cmp_spec: &props.cmp_spec,
                 ty_inner,
-                ty_lhs: quote!(::std::borrow::Cow<#new_lt0, #ty_outer #type_generics>),
+                ty_lhs: quote!(#cow<#new_lt0, #ty_outer #type_generics>),
                 lhs_self_as_inner: &props.tokens_outer_expr_as_inner(quote!(&*self)),
                 ty_rhs: quote!(&#new_lt1 #ty_outer #type_generics),
                 rhs_other_as_inner: &props.tokens_outer_expr_as_inner(quote!(*other)),
@@ -289,6 +385,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
             }
         }
         Derive::PartialEqSelfCowRev | Derive::PartialOrdSelfCowRev => {
+            let cow = tokens_cow(target, props)?;
             let (generics, new_lts) = extend_generics(Cow::Borrowed(props.generics), 1, &[]);
             let new_lt = &new_lts[0];
             let outer_cow_and_outer_rev = CmpImplSpec {
@@ -299,7 +396,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
                 ty_inner,
                 ty_lhs: quote!(#ty_outer #type_generics),
                 lhs_self_as_inner: &self_as_inner,
-                ty_rhs: quote!(::std::borrow::Cow<#new_lt, #ty_outer #type_generics>),
+                ty_rhs: quote!(#cow<#new_lt, #ty_outer #type_generics>),
                 rhs_other_as_inner: &props.tokens_outer_expr_as_inner(quote!(&*other)),
             }
             .gen_impl();
@@ -314,7 +411,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
                 ty_inner,
                 ty_lhs: quote!(&#new_lt0 #ty_outer #type_generics),
                 lhs_self_as_inner: &props.tokens_outer_expr_as_inner(quote!(*self)),
-                ty_rhs: quote!(::std::borrow::Cow<#new_lt1, #ty_outer #type_generics>),
+                ty_rhs: quote!(#cow<#new_lt1, #ty_outer #type_generics>),
                 rhs_other_as_inner: &props.tokens_outer_expr_as_inner(quote!(&*other)),
             }
             .gen_impl();
@@ -324,6 +421,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
             }
         }
         Derive::PartialEqSelfCowAndInner | Derive::PartialOrdSelfCowAndInner => {
+            let cow = tokens_cow(target, props)?;
             let (generics, new_lts) = extend_generics(Cow::Borrowed(props.generics), 1, &[]);
             let new_lt = &new_lts[0];
             let outer_cow_and_inner = CmpImplSpec {
@@ -332,7 +430,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
                 trait_spec,
                 cmp_spec: &props.cmp_spec,
                 ty_inner,
-                ty_lhs: quote!(::std::borrow::Cow<#new_lt, #ty_outer #type_generics>),
+                ty_lhs: quote!(#cow<#new_lt, #ty_outer #type_generics>),
                 lhs_self_as_inner: &props.tokens_outer_expr_as_inner(quote!(&*self)),
                 ty_rhs: ty_inner,
                 rhs_other_as_inner: &quote!(other),
@@ -347,7 +445,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
                 trait_spec,
                 cmp_spec: &props.cmp_spec,
                 ty_inner,
-                ty_lhs: quote!(::std::borrow::Cow<#new_lt0, #ty_outer #type_generics>),
+                ty_lhs: quote!(#cow<#new_lt0, #ty_outer #type_generics>),
                 lhs_self_as_inner: &props.tokens_outer_expr_as_inner(quote!(&*self)),
                 ty_rhs: quote!(&#new_lt1 #ty_inner),
                 rhs_other_as_inner: &quote!(*other),
@@ -359,6 +457,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
             }
         }
         Derive::PartialEqSelfCowAndInnerRev | Derive::PartialOrdSelfCowAndInnerRev => {
+            let cow = tokens_cow(target, props)?;
             let (generics, new_lts) = extend_generics(Cow::Borrowed(props.generics), 1, &[]);
             let new_lt = &new_lts[0];
             let outer_cow_and_inner_rev = CmpImplSpec {
@@ -369,7 +468,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
                 ty_inner,
                 ty_lhs: ty_inner,
                 lhs_self_as_inner: &quote!(self),
-                ty_rhs: quote!(::std::borrow::Cow<#new_lt, #ty_outer #type_generics>),
+                ty_rhs: quote!(#cow<#new_lt, #ty_outer #type_generics>),
                 rhs_other_as_inner: &props.tokens_outer_expr_as_inner(quote!(&*other)),
             }
             .gen_impl();
@@ -384,7 +483,7 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
                 ty_inner,
                 ty_lhs: quote!(&#new_lt0 #ty_inner),
                 lhs_self_as_inner: &quote!(*self),
-                ty_rhs: quote!(::std::borrow::Cow<#new_lt1, #ty_outer #type_generics>),
+                ty_rhs: quote!(#cow<#new_lt1, #ty_outer #type_generics>),
                 rhs_other_as_inner: &props.tokens_outer_expr_as_inner(quote!(&*other)),
             }
             .gen_impl();
@@ -394,7 +493,178 @@ pub fn gen_impl_partial_cmp(target: Derive, props: &TypeProps) -> TokenStream {
             }
         }
         _ => unreachable!("Should never happen"),
+    };
+    Ok(tokens)
+}
+
+/// Generates an impl for the comparison with the deref target.
+///
+/// Comparators are functions of the inner type, so the comparison uses the
+/// trait impls of the deref target, and custom comparators are refused.
+fn gen_impl_partial_cmp_deref(
+    target: Derive,
+    trait_spec: CmpTraitSpec,
+    props: &TypeProps,
+) -> syn::Result<TokenStream> {
+    let cmp_spec = &props.cmp_spec;
+    let has_custom_cmp = cmp_spec.preset_span().is_some()
+        || cmp_spec.constant_time.is_some()
+        || match trait_spec {
+            CmpTraitSpec::PartialEq => cmp_spec.partial_eq.is_some(),
+            CmpTraitSpec::PartialOrd => cmp_spec.partial_ord.is_some(),
+            CmpTraitSpec::Ord => unreachable!("Should never happen"),
+        };
+    if has_custom_cmp {
+        return Err(syn::Error::new(
+            props.derive_span(target),
+            format!(
+                "`#[opaque_typedef(derive({}))]` cannot be used with custom comparators, \
+                 because they compare the inner values but not the deref targets",
+                target.as_ref()
+            ),
+        ));
     }
+    let default_cmp_spec = CmpSpec::default();
+    let ty_outer = &props.ty_outer;
+    let type_generics = &props.type_generics;
+    let ty_target = props.tokens_ty_deref_target();
+    let self_as_target = props.tokens_outer_expr_as_deref_target(quote!(self));
+    let other_as_target = props.tokens_outer_expr_as_deref_target(quote!(other));
+    let (generics, new_lts) = extend_generics(Cow::Borrowed(props.generics), 1, &[]);
+    let new_lt = &new_lts[0];
+    let skip_refs = props.inner_sizedness == Sizedness::Sized && props.has_type_params();
+    let tokens = match target {
+        Derive::PartialEqDeref | Derive::PartialOrdDeref => {
+            let target_and_outer = CmpImplSpec {
+                type_props: props,
+                generics: props.generics,
+                trait_spec,
+                cmp_spec: &default_cmp_spec,
+                ty_inner: &ty_target,
+                ty_lhs: quote!(#ty_outer #type_generics),
+                lhs_self_as_inner: &self_as_target,
+                ty_rhs: &ty_target,
+                rhs_other_as_inner: &quote!(other),
+            }
+            .gen_impl();
+            let extra = if skip_refs {
+                quote!()
+            } else {
+                let target_and_outer_ref = CmpImplSpec {
+                    type_props: props,
+                    generics: &generics,
+                    trait_spec,
+                    cmp_spec: &default_cmp_spec,
+                    ty_inner: &ty_target,
+                    ty_lhs: quote!(&#new_lt #ty_outer #type_generics),
+                    lhs_self_as_inner: &props.tokens_outer_expr_as_deref_target(quote!(*self)),
+                    ty_rhs: &ty_target,
+                    rhs_other_as_inner: &quote!(other),
+                }
+                .gen_impl();
+                let target_ref_and_outer = CmpImplSpec {
+                    type_props: props,
+                    generics: &generics,
+                    trait_spec,
+                    cmp_spec: &default_cmp_spec,
+                    ty_inner: &ty_target,
+                    ty_lhs: quote!(#ty_outer #type_generics),
+                    lhs_self_as_inner: &self_as_target,
+                    ty_rhs: quote!(&#new_lt #ty_target),
+                    rhs_other_as_inner: &quote!(*other),
+                }
+                .gen_impl();
+                quote! {
+                    #target_and_outer_ref
+                    #target_ref_and_outer
+                }
+            };
+            quote! {
+                #target_and_outer
+                #extra
+            }
+        }
+        Derive::PartialEqDerefRev | Derive::PartialOrdDerefRev => {
+            let target_and_outer_rev = CmpImplSpec {
+                type_props: props,
+                generics: props.generics,
+                trait_spec,
+                cmp_spec: &default_cmp_spec,
+                ty_inner: &ty_target,
+                ty_lhs: &ty_target,
+                lhs_self_as_inner: &quote!(self),
+                ty_rhs: quote!(#ty_outer #type_generics),
+                rhs_other_as_inner: &other_as_target,
+            }
+            .gen_impl();
+            let extra = if skip_refs {
+                quote!()
+            } else {
+                let target_and_outer_ref_rev = CmpImplSpec {
+                    type_props: props,
+                    generics: &generics,
+                    trait_spec,
+                    cmp_spec: &default_cmp_spec,
+                    ty_inner: &ty_target,
+                    ty_lhs: &ty_target,
+                    lhs_self_as_inner: &quote!(self),
+                    ty_rhs: quote!(&#new_lt #ty_outer #type_generics),
+                    rhs_other_as_inner: &props.tokens_outer_expr_as_deref_target(quote!(*other)),
+                }
+                .gen_impl();
+                let target_ref_and_outer_rev = CmpImplSpec {
+                    type_props: props,
+                    generics: &generics,
+                    trait_spec,
+                    cmp_spec: &default_cmp_spec,
+                    ty_inner: &ty_target,
+                    ty_lhs: quote!(&#new_lt #ty_target),
+                    lhs_self_as_inner: &quote!(*self),
+                    ty_rhs: quote!(#ty_outer #type_generics),
+                    rhs_other_as_inner: &other_as_target,
+                }
+                .gen_impl();
+                quote! {
+                    #target_and_outer_ref_rev
+                    #target_ref_and_outer_rev
+                }
+            };
+            quote! {
+                #target_and_outer_rev
+                #extra
+            }
+        }
+        _ => unreachable!("Should never happen"),
+    };
+    Ok(tokens)
+}
+
+/// Returns an error if the ordering comparison is refused for the type.
+///
+/// Ordering comparison cannot be implemented in constant time, so it is
+/// refused for types with `cmp(constant_time)`.
+fn check_ordering_allowed(target: Derive, props: &TypeProps) -> syn::Result<()> {
+    if props.cmp_spec.constant_time.is_some() {
+        return Err(syn::Error::new(
+            props.derive_span(target),
+            format!(
+                "`#[opaque_typedef(derive({}))]` cannot be used with \
+                 `#[opaque_typedef(cmp(constant_time))]`, \
+                 because ordering comparison cannot be done in constant time",
+                target.as_ref()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the path to `Cow` type.
+fn tokens_cow(target: Derive, props: &TypeProps) -> syn::Result<TokenStream> {
+    let alloc = paths::alloc(
+        props.derive_span(target),
+        format_args!("`#[opaque_typedef(derive({}))]`", target.as_ref()),
+    )?;
+    Ok(quote!(#alloc::borrow::Cow))
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -406,10 +676,11 @@ enum CmpTraitSpec {
 
 impl CmpTraitSpec {
     pub fn target_trait(self) -> TokenStream {
+        let core = paths::core();
         match self {
-            CmpTraitSpec::PartialEq => quote!(::std::cmp::PartialEq),
-            CmpTraitSpec::PartialOrd => quote!(::std::cmp::PartialOrd),
-            CmpTraitSpec::Ord => quote!(::std::cmp::Ord),
+            CmpTraitSpec::PartialEq => quote!(#core::cmp::PartialEq),
+            CmpTraitSpec::PartialOrd => quote!(#core::cmp::PartialOrd),
+            CmpTraitSpec::Ord => quote!(#core::cmp::Ord),
         }
     }
 
@@ -430,10 +701,11 @@ impl CmpTraitSpec {
     }
 
     pub fn ty_ret(self) -> TokenStream {
+        let core = paths::core();
         match self {
             CmpTraitSpec::PartialEq => quote!(bool),
-            CmpTraitSpec::PartialOrd => quote!(Option<::std::cmp::Ordering>),
-            CmpTraitSpec::Ord => quote!(::std::cmp::Ordering),
+            CmpTraitSpec::PartialOrd => quote!(Option<#core::cmp::Ordering>),
+            CmpTraitSpec::Ord => quote!(#core::cmp::Ordering),
         }
     }
 }
@@ -470,7 +742,8 @@ where
             rhs_other_as_inner,
         } = *self;
         let target_trait = trait_spec.target_trait();
-        let extra_preds = if type_props.has_type_params() {
+        // Preset comparators don't require the traits of the inner type.
+        let extra_preds = if type_props.has_type_params() && cmp_spec.uses_inner_traits() {
             let ty_inner = ty_inner.into_token_stream();
             let pred = match *trait_spec {
                 CmpTraitSpec::PartialEq | CmpTraitSpec::PartialOrd => {
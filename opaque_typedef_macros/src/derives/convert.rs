@@ -1,4 +1,5 @@
-//! Impl generators for `std::convert::{From,Inner}` traits.
+//! Impl generators for `std::convert::{From,Into,TryFrom}` and
+//! `std::str::FromStr` traits.
 
 use std::borrow::Cow;
 
@@ -6,13 +7,15 @@ use proc_macro2::TokenStream;
 use quote::ToTokens;
 use syn;
 
-use type_props::{Sizedness, TypeProps};
+use paths;
+use type_props::{DisplaySpec, Sizedness, TypeProps};
 use utils::extend_generics;
 
 use super::Derive;
 
 /// Generates an impl for the target.
 pub fn gen_impl_from_inner(props: &TypeProps) -> TokenStream {
+    let core = paths::core();
     let helper_trait = props.helper_trait();
     let ty_outer = props.ty_outer.into_token_stream();
     let ty_inner = props.field_inner.ty().into_token_stream();
@@ -22,10 +25,10 @@ pub fn gen_impl_from_inner(props: &TypeProps) -> TokenStream {
     };
     match props.inner_sizedness {
         Sizedness::Sized => {
-            let impl_generics = &props.impl_generics;
-            let where_clause = &props.where_clause;
+            let generics = props.generics_with_helper_trait_preds();
+            let (impl_generics, _, where_clause) = generics.split_for_impl();
             quote! {
-                impl #impl_generics ::std::convert::From<#ty_inner> for #ty_outer #type_generics
+                impl #impl_generics #core::convert::From<#ty_inner> for #ty_outer #type_generics
                 #where_clause
                 {
                     fn from(__inner: #ty_inner) -> Self {
@@ -35,12 +38,13 @@ pub fn gen_impl_from_inner(props: &TypeProps) -> TokenStream {
             }
         }
         Sizedness::Unsized => {
-            let (generics, new_lifetimes) = extend_generics(Cow::Borrowed(props.generics), 1, &[]);
+            let (generics, new_lifetimes) =
+                extend_generics(props.generics_with_helper_trait_preds(), 1, &[]);
             let (impl_generics, _, where_clause) = generics.split_for_impl();
             let new_lt = &new_lifetimes[0];
             quote! {
                 impl #impl_generics
-                    ::std::convert::From<&#new_lt #ty_inner> for &#new_lt #ty_outer #type_generics
+                    #core::convert::From<&#new_lt #ty_inner> for &#new_lt #ty_outer #type_generics
                 #where_clause
                 {
                     fn from(__inner: &#new_lt #ty_inner) -> Self {
@@ -53,21 +57,132 @@ pub fn gen_impl_from_inner(props: &TypeProps) -> TokenStream {
 }
 
 /// Generates an impl for the target.
-pub fn gen_impl_into_inner(props: &TypeProps) -> TokenStream {
+pub fn gen_impl_try_from_inner(props: &TypeProps) -> TokenStream {
+    let core = paths::core();
     let helper_trait = props.helper_trait();
     let ty_outer = props.ty_outer.into_token_stream();
+    let ty_inner = props.field_inner.ty().into_token_stream();
+    let type_generics = &props.type_generics;
+    let ty_error = props.validation_spec.tokens_ty_error();
+    match props.inner_sizedness {
+        Sizedness::Sized => {
+            let generics = props.generics_with_helper_trait_preds();
+            let (impl_generics, _, where_clause) = generics.split_for_impl();
+            quote! {
+                impl #impl_generics #core::convert::TryFrom<#ty_inner> for #ty_outer #type_generics
+                #where_clause
+                {
+                    type Error = #ty_error;
+
+                    fn try_from(__inner: #ty_inner) -> #core::result::Result<Self, Self::Error> {
+                        <#ty_outer #type_generics as #helper_trait>::try_from_inner(__inner)
+                    }
+                }
+            }
+        }
+        Sizedness::Unsized => {
+            let (generics, new_lifetimes) =
+                extend_generics(props.generics_with_helper_trait_preds(), 1, &[]);
+            let (impl_generics, _, where_clause) = generics.split_for_impl();
+            let new_lt = &new_lifetimes[0];
+            quote! {
+                impl #impl_generics
+                    #core::convert::TryFrom<&#new_lt #ty_inner> for &#new_lt #ty_outer #type_generics
+                #where_clause
+                {
+                    type Error = #ty_error;
+
+                    fn try_from(
+                        __inner: &#new_lt #ty_inner
+                    ) -> #core::result::Result<Self, Self::Error> {
+                        <#ty_outer #type_generics as #helper_trait>::try_from_inner(__inner)
+                    }
+                }
+
+                impl #impl_generics
+                    #core::convert::TryFrom<&#new_lt mut #ty_inner>
+                    for &#new_lt mut #ty_outer #type_generics
+                #where_clause
+                {
+                    type Error = #ty_error;
+
+                    fn try_from(
+                        __inner: &#new_lt mut #ty_inner
+                    ) -> #core::result::Result<Self, Self::Error> {
+                        <#ty_outer #type_generics as #helper_trait>::try_from_inner_mut(__inner)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Generates an impl for the target.
+///
+/// If the value can be validated without taking the ownership, the inner
+/// value is cloned only when the validation succeeded.
+pub fn gen_impl_try_from_inner_ref(props: &TypeProps) -> TokenStream {
+    let core = paths::core();
+    let helper_trait = props.helper_trait();
+    let ty_outer = props.ty_outer.into_token_stream();
+    let ty_inner = props.field_inner.ty().into_token_stream();
+    let type_generics = &props.type_generics;
+    let ty_error = props.validation_spec.tokens_ty_error();
+    let mut extra_preds = props.helper_trait_preds();
+    if props.has_type_params() {
+        let pred =
+            syn::parse_str::<syn::WherePredicate>(&format!("{}: {}::clone::Clone", ty_inner, core))
+                .expect("Failed to generate `WherePredicate`");
+        extra_preds.push(pred);
+    }
+    let (generics, new_lifetimes) = extend_generics(Cow::Borrowed(props.generics), 1, &extra_preds);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let new_lt = &new_lifetimes[0];
+    let body = if props.validation_spec.is_borrowed_check_available() {
+        quote! {
+            <#ty_outer #type_generics as #helper_trait>::validate(__inner)?;
+            let __inner = <#ty_inner as #core::clone::Clone>::clone(__inner);
+            Ok(unsafe { <#ty_outer #type_generics as #helper_trait>::from_inner_unchecked(__inner) })
+        }
+    } else {
+        // The normalizer or the validator may modify the value, so the
+        // validated value should be used.
+        quote! {
+            let __inner = <#ty_inner as #core::clone::Clone>::clone(__inner);
+            <#ty_outer #type_generics as #helper_trait>::try_from_inner(__inner)
+        }
+    };
+    quote! {
+        impl #impl_generics
+            #core::convert::TryFrom<&#new_lt #ty_inner> for #ty_outer #type_generics
+        #where_clause
+        {
+            type Error = #ty_error;
+
+            fn try_from(__inner: &#new_lt #ty_inner) -> #core::result::Result<Self, Self::Error> {
+                #body
+            }
+        }
+    }
+}
+
+/// Generates an impl for the target.
+pub fn gen_impl_into_inner(props: &TypeProps) -> TokenStream {
+    let core = paths::core();
+    let ty_outer = props.ty_outer.into_token_stream();
     let type_generics = &props.type_generics;
     let ty_inner = props.field_inner.ty().into_token_stream();
     match props.inner_sizedness {
         Sizedness::Sized => {
             let impl_generics = &props.impl_generics;
             let where_clause = &props.where_clause;
+            let self_into_inner = props.tokens_outer_expr_into_inner(quote!(self));
             quote! {
-                impl #impl_generics ::std::convert::Into<#ty_inner> for #ty_outer #type_generics
+                impl #impl_generics #core::convert::Into<#ty_inner> for #ty_outer #type_generics
                 #where_clause
                 {
                     fn into(self) -> #ty_inner {
-                        <#ty_outer #type_generics as #helper_trait>::into_inner(self)
+                        #self_into_inner
                     }
                 }
             }
@@ -76,13 +191,14 @@ pub fn gen_impl_into_inner(props: &TypeProps) -> TokenStream {
             let (generics, new_lifetimes) = extend_generics(Cow::Borrowed(props.generics), 1, &[]);
             let (impl_generics, _, where_clause) = generics.split_for_impl();
             let new_lt = &new_lifetimes[0];
+            let self_as_inner = props.tokens_outer_expr_as_inner(quote!(self));
             quote! {
                 impl #impl_generics
-                    ::std::convert::Into<&#new_lt #ty_inner> for &#new_lt #ty_outer #type_generics
+                    #core::convert::Into<&#new_lt #ty_inner> for &#new_lt #ty_outer #type_generics
                 #where_clause
                 {
                     fn into(self) -> &#new_lt #ty_inner {
-                        <#ty_outer #type_generics as #helper_trait>::as_inner(self)
+                        #self_as_inner
                     }
                 }
             }
@@ -91,38 +207,43 @@ pub fn gen_impl_into_inner(props: &TypeProps) -> TokenStream {
 }
 
 /// Generates an impl for the target.
-pub fn gen_impl_into_smartptr(target: Derive, props: &TypeProps) -> TokenStream {
+pub fn gen_impl_into_smartptr(target: Derive, props: &TypeProps) -> syn::Result<TokenStream> {
     assert_eq!(
         props.inner_sizedness,
         Sizedness::Unsized,
         "opaque_typedef internal error: `gen_impl_into_smartptr()` works only for unsized types"
     );
 
+    let core = paths::core();
+    let alloc = paths::alloc(
+        props.derive_span(target),
+        format_args!("`#[opaque_typedef(derive({}))]`", target.as_ref()),
+    )?;
     let ty_inner = props.field_inner.ty().into_token_stream();
     let ty_outer = props.ty_outer.into_token_stream();
     let type_generics = &props.type_generics;
     let (ty_smartptr_inner, ty_target, fn_to_inner_smartptr, fn_into_raw, fn_from_raw) =
         match target {
             Derive::IntoArc => (
-                quote!(::std::sync::Arc<#ty_inner>),
-                quote!(::std::sync::Arc<#ty_outer #type_generics>),
-                quote!(::std::sync::Arc::<#ty_inner>::from),
-                quote!(::std::sync::Arc::into_raw),
-                quote!(::std::sync::Arc::from_raw),
+                quote!(#alloc::sync::Arc<#ty_inner>),
+                quote!(#alloc::sync::Arc<#ty_outer #type_generics>),
+                quote!(#alloc::sync::Arc::<#ty_inner>::from),
+                quote!(#alloc::sync::Arc::into_raw),
+                quote!(#alloc::sync::Arc::from_raw),
             ),
             Derive::IntoBox => (
-                quote!(::std::boxed::Box<#ty_inner>),
-                quote!(::std::boxed::Box<#ty_outer #type_generics>),
-                quote!(::std::boxed::Box::<#ty_inner>::from),
-                quote!(::std::boxed::Box::into_raw),
-                quote!(::std::boxed::Box::from_raw),
+                quote!(#alloc::boxed::Box<#ty_inner>),
+                quote!(#alloc::boxed::Box<#ty_outer #type_generics>),
+                quote!(#alloc::boxed::Box::<#ty_inner>::from),
+                quote!(#alloc::boxed::Box::into_raw),
+                quote!(#alloc::boxed::Box::from_raw),
             ),
             Derive::IntoRc => (
-                quote!(::std::rc::Rc<#ty_inner>),
-                quote!(::std::rc::Rc<#ty_outer #type_generics>),
-                quote!(::std::rc::Rc::<#ty_inner>::from),
-                quote!(::std::rc::Rc::into_raw),
-                quote!(::std::rc::Rc::from_raw),
+                quote!(#alloc::rc::Rc<#ty_inner>),
+                quote!(#alloc::rc::Rc<#ty_outer #type_generics>),
+                quote!(#alloc::rc::Rc::<#ty_inner>::from),
+                quote!(#alloc::rc::Rc::into_raw),
+                quote!(#alloc::rc::Rc::from_raw),
             ),
             _ => unreachable!("Should never happen"),
         };
@@ -152,9 +273,9 @@ pub fn gen_impl_into_smartptr(target: Derive, props: &TypeProps) -> TokenStream
     // course we can't use `From` impls (such as `Arc::<[T]>::from(my_slice)`).
     if props.has_type_params() {
         let self_as_inner = props.tokens_outer_expr_as_inner(quote!(self));
-        quote! {
+        Ok(quote! {
             impl #impl_generics
-                ::std::convert::Into<#ty_target> for &#new_lt #ty_outer #type_generics
+                #core::convert::Into<#ty_target> for &#new_lt #ty_outer #type_generics
             #where_clause
             {
                 fn into(self) -> #ty_target {
@@ -163,12 +284,12 @@ pub fn gen_impl_into_smartptr(target: Derive, props: &TypeProps) -> TokenStream
                     unsafe { #fn_from_raw(raw) }
                 }
             }
-        }
+        })
     } else {
         let other_as_inner = props.tokens_outer_expr_as_inner(quote!(__other));
-        quote! {
+        Ok(quote! {
             impl #impl_generics
-                ::std::convert::From<&#new_lt #ty_outer #type_generics> for #ty_target
+                #core::convert::From<&#new_lt #ty_outer #type_generics> for #ty_target
             #where_clause
             {
                 fn from(__other: &#new_lt #ty_outer #type_generics) -> Self {
@@ -177,6 +298,90 @@ pub fn gen_impl_into_smartptr(target: Derive, props: &TypeProps) -> TokenStream
                     unsafe { #fn_from_raw(raw) }
                 }
             }
+        })
+    }
+}
+
+/// Generates an impl for `FromStr`.
+///
+/// If `#[opaque_typedef(display_fmt = ..)]` is specified, the string is
+/// parsed back from the format.
+pub fn gen_impl_from_str(props: &TypeProps) -> syn::Result<TokenStream> {
+    let core = paths::core();
+    let helper_trait = props.helper_trait();
+    let ty_outer = props.ty_outer.into_token_stream();
+    let ty_inner = props.field_inner.ty().into_token_stream();
+    let type_generics = &props.type_generics;
+    let parse_error = quote!(::opaque_typedef::ParseError);
+    let strip_affixes = match props.display_spec {
+        None => quote!(),
+        Some(DisplaySpec::Fn(ref expr)) => {
+            return Err(syn::Error::new(
+                props.derive_span(Derive::FromStr),
+                format!(
+                    "`#[opaque_typedef(derive(FromStr))]` cannot parse the format of \
+                     `#[opaque_typedef(display = {:?})]`; use `display_fmt` instead",
+                    expr.into_token_stream().to_string()
+                ),
+            ));
         }
+        Some(DisplaySpec::Fmt {
+            ref fmt,
+            has_format_spec: true,
+            ..
+        }) => {
+            return Err(syn::Error::new(
+                props.derive_span(Derive::FromStr),
+                format!(
+                    "`#[opaque_typedef(derive(FromStr))]` cannot parse the format of \
+                     `#[opaque_typedef(display_fmt = {:?})]`, because the placeholder has \
+                     format spec",
+                    fmt.value()
+                ),
+            ));
+        }
+        Some(DisplaySpec::Fmt {
+            ref prefix,
+            ref suffix,
+            ..
+        }) => {
+            let len_affixes = prefix.len() + suffix.len();
+            quote! {
+                if __s.len() < #len_affixes
+                    || !__s.starts_with(#prefix)
+                    || !__s.ends_with(#suffix)
+                {
+                    return #core::result::Result::Err(#parse_error::Format);
+                }
+                let __s = &__s[#prefix.len()..(__s.len() - #suffix.len())];
+            }
+        }
+    };
+    let mut extra_preds = props.helper_trait_preds();
+    if props.has_type_params() {
+        let pred =
+            syn::parse_str::<syn::WherePredicate>(&format!("{}: {}::str::FromStr", ty_inner, core))
+                .expect("Failed to generate `WherePredicate`");
+        extra_preds.push(pred);
     }
+    let (generics, _) = extend_generics(Cow::Borrowed(props.generics), 0, &extra_preds);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #core::str::FromStr for #ty_outer #type_generics
+        #where_clause
+        {
+            type Err = #parse_error<
+                <#ty_inner as #core::str::FromStr>::Err,
+                <#ty_outer #type_generics as #helper_trait>::Error,
+            >;
+
+            fn from_str(__s: &str) -> #core::result::Result<Self, Self::Err> {
+                #strip_affixes
+                let __inner = <#ty_inner as #core::str::FromStr>::from_str(__s)
+                    .map_err(#parse_error::Inner)?;
+                <#ty_outer #type_generics as #helper_trait>::try_from_inner(__inner)
+                    .map_err(#parse_error::Validation)
+            }
+        }
+    })
 }
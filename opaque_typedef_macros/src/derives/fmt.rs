@@ -6,33 +6,60 @@ use proc_macro2::TokenStream;
 use quote::ToTokens;
 use syn;
 
-use type_props::TypeProps;
+use paths;
+use type_props::{DisplaySpec, TypeProps};
 use utils::extend_generics;
 
 use super::Derive;
 
 /// Generates an impl for the target.
-pub fn gen_impl(target: Derive, props: &TypeProps) -> TokenStream {
-    let trait_name = match target {
-        Derive::Binary => quote!(Binary),
-        Derive::Display => quote!(Display),
-        Derive::LowerExp => quote!(LowerExp),
-        Derive::LowerHex => quote!(LowerHex),
-        Derive::Octal => quote!(Octal),
-        Derive::Pointer => quote!(Pointer),
-        Derive::UpperExp => quote!(UpperExp),
-        Derive::UpperHex => quote!(UpperHex),
+///
+/// `*Deref` targets format the deref target instead of the inner value.
+pub fn gen_impl(target: Derive, props: &TypeProps) -> syn::Result<TokenStream> {
+    let (trait_name, via_inner) = match target {
+        Derive::Binary | Derive::BinaryDeref => (quote!(Binary), Derive::Binary),
+        Derive::Display | Derive::DisplayDeref => (quote!(Display), Derive::Display),
+        Derive::LowerExp | Derive::LowerExpDeref => (quote!(LowerExp), Derive::LowerExp),
+        Derive::LowerHex | Derive::LowerHexDeref => (quote!(LowerHex), Derive::LowerHex),
+        Derive::Octal | Derive::OctalDeref => (quote!(Octal), Derive::Octal),
+        Derive::Pointer | Derive::PointerDeref => (quote!(Pointer), Derive::Pointer),
+        Derive::UpperExp | Derive::UpperExpDeref => (quote!(UpperExp), Derive::UpperExp),
+        Derive::UpperHex | Derive::UpperHexDeref => (quote!(UpperHex), Derive::UpperHex),
         _ => unreachable!("Should never happen"),
     };
+    if let (Derive::Display, Some(spec)) = (target, props.display_spec.as_ref()) {
+        return Ok(gen_impl_display_custom(spec, props));
+    }
+    let is_deref = target != via_inner;
+    if is_deref && props.derives.contains(&via_inner) {
+        return Err(syn::Error::new(
+            props.derive_span(target),
+            format!(
+                "`#[opaque_typedef(derive({}))]` cannot be used with \
+                 `#[opaque_typedef(derive({}))]`",
+                target.as_ref(),
+                via_inner.as_ref()
+            ),
+        ));
+    }
+    let core = paths::core();
     let ty_outer = props.ty_outer;
     let type_generics = &props.type_generics;
-    let ty_inner = props.field_inner.ty();
-    let self_as_inner = props.tokens_outer_expr_as_inner(quote!(self));
+    let (ty_formatted, self_formatted) = if is_deref {
+        (
+            props.tokens_ty_deref_target(),
+            props.tokens_outer_expr_as_deref_target(quote!(self)),
+        )
+    } else {
+        (
+            props.field_inner.ty().into_token_stream(),
+            props.tokens_outer_expr_as_inner(quote!(self)),
+        )
+    };
     let extra_preds = if props.has_type_params() {
-        let ty_inner = ty_inner.into_token_stream();
         let pred = syn::parse_str::<syn::WherePredicate>(&format!(
-            "{}: ::std::fmt::{}",
-            ty_inner, trait_name,
+            "{}: {}::fmt::{}",
+            ty_formatted, core, trait_name,
         ))
         .expect("Failed to generate `WherePredicate`");
         vec![pred]
@@ -41,11 +68,109 @@ pub fn gen_impl(target: Derive, props: &TypeProps) -> TokenStream {
     };
     let (generics, _) = extend_generics(Cow::Borrowed(props.generics), 0, &extra_preds);
     let (impl_generics, _, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #core::fmt::#trait_name for #ty_outer #type_generics #where_clause {
+            fn fmt(&self, f: &mut #core::fmt::Formatter) -> #core::fmt::Result {
+                <#ty_formatted as #core::fmt::#trait_name>::fmt(#self_formatted, f)
+            }
+        }
+    })
+}
+
+/// Generates an impl for `Display` with the custom function or format.
+fn gen_impl_display_custom(spec: &DisplaySpec, props: &TypeProps) -> TokenStream {
+    let core = paths::core();
+    let ty_outer = props.ty_outer;
+    let type_generics = &props.type_generics;
+    let ty_inner = props.field_inner.ty();
+    let self_as_inner = props.tokens_outer_expr_as_inner(quote!(self));
+    let body = match *spec {
+        DisplaySpec::Fn(ref fn_display) => quote!(#fn_display(#self_as_inner as &#ty_inner, f)),
+        DisplaySpec::Fmt { ref fmt, .. } => quote!(write!(f, #fmt, #self_as_inner)),
+    };
+    let (impl_generics, _, where_clause) = props.generics.split_for_impl();
     quote! {
-        impl #impl_generics ::std::fmt::#trait_name for #ty_outer #type_generics #where_clause {
-            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
-                <#ty_inner as ::std::fmt::#trait_name>::fmt(#self_as_inner, f)
+        impl #impl_generics #core::fmt::Display for #ty_outer #type_generics #where_clause {
+            fn fmt(&self, f: &mut #core::fmt::Formatter) -> #core::fmt::Result {
+                #body
             }
         }
     }
 }
+
+/// Debug derive targets.
+const DEBUG_DERIVES: &[Derive] = &[
+    Derive::DebugDeref,
+    Derive::DebugNamed,
+    Derive::DebugTransparent,
+];
+
+/// Generates an impl for `Debug` targets.
+pub fn gen_impl_debug(target: Derive, props: &TypeProps) -> syn::Result<TokenStream> {
+    // Only one of `Debug*` targets can be used.
+    let first = props
+        .derives
+        .iter()
+        .cloned()
+        .find(|derive| DEBUG_DERIVES.contains(derive))
+        .expect("Should never happen: the target itself should be found");
+    if first != target {
+        return Err(syn::Error::new(
+            props.derive_span(target),
+            format!(
+                "`#[opaque_typedef(derive({}))]` cannot be used with \
+                 `#[opaque_typedef(derive({}))]`",
+                target.as_ref(),
+                first.as_ref()
+            ),
+        ));
+    }
+    let core = paths::core();
+    let ty_outer = props.ty_outer;
+    let type_generics = &props.type_generics;
+    let ty_inner = props.field_inner.ty().into_token_stream();
+    let self_as_inner = props.tokens_outer_expr_as_inner(quote!(self));
+    let (ty_formatted, body) = match target {
+        Derive::DebugDeref => {
+            let ty_deref_target = props.tokens_ty_deref_target();
+            let self_deref = props.tokens_outer_expr_as_deref_target(quote!(self));
+            let body = quote! {
+                <#ty_deref_target as #core::fmt::Debug>::fmt(#self_deref, f)
+            };
+            (ty_deref_target, body)
+        }
+        Derive::DebugNamed => {
+            let name = ty_outer.to_string();
+            let body = quote! {
+                f.debug_tuple(#name).field(&#self_as_inner).finish()
+            };
+            (ty_inner, body)
+        }
+        Derive::DebugTransparent => {
+            let body = quote! {
+                <#ty_inner as #core::fmt::Debug>::fmt(#self_as_inner, f)
+            };
+            (ty_inner, body)
+        }
+        _ => unreachable!("Should never happen"),
+    };
+    let extra_preds = if props.has_type_params() {
+        let pred = syn::parse_str::<syn::WherePredicate>(&format!(
+            "{}: {}::fmt::Debug",
+            ty_formatted, core,
+        ))
+        .expect("Failed to generate `WherePredicate`");
+        vec![pred]
+    } else {
+        Vec::new()
+    };
+    let (generics, _) = extend_generics(Cow::Borrowed(props.generics), 0, &extra_preds);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #core::fmt::Debug for #ty_outer #type_generics #where_clause {
+            fn fmt(&self, f: &mut #core::fmt::Formatter) -> #core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
@@ -3,22 +3,34 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::ToTokens;
 use syn;
 
 use attrs::{get_meta_content_by_path, is_attr_with_path};
+use paths;
 use type_props::{Sizedness, TypeProps};
 use utils::extend_generics;
 
 use self::ops::OpSpec;
 
+pub use self::borrow::gen_impls_borrow_pair;
+pub use self::secret::gen_impls_secret;
+pub use self::validation::{
+    gen_impl_check_normalized, gen_impl_check_validator, gen_impl_from_inner_cow,
+    gen_impls_auto_error,
+};
+
 mod as_ref;
+mod borrow;
 mod cmp;
 mod convert;
 mod deref;
 mod fmt;
 mod ops;
+mod secret;
+mod serde;
+mod validation;
 
 /// Auto-derive target trait.
 #[derive(
@@ -473,16 +485,34 @@ pub enum Derive {
     AsRefSelf,
     /// `std::fmt::Binary for Outer`.
     Binary,
+    /// `std::fmt::Binary for Outer`, formatting the deref target.
+    BinaryDeref,
+    /// `std::fmt::Debug for Outer`, formatting the deref target.
+    DebugDeref,
+    /// `std::fmt::Debug for Outer`, formatting as `Outer(inner)`.
+    DebugNamed,
+    /// `std::fmt::Debug for Outer`, formatting exactly like the inner value.
+    DebugTransparent,
     /// `Default for &Outer`.
     DefaultRef,
     /// `std::ops::Deref for Outer`.
     Deref,
     /// `std::ops::DerefMut for Outer`.
     DerefMut,
+    /// `serde::Deserialize for Outer` (or `&Outer` for unsized types).
+    Deserialize,
     /// `std::fmt::Display for Outer`.
     Display,
+    /// `std::fmt::Display for Outer`, formatting the deref target.
+    DisplayDeref,
+    /// `std::cmp::Eq for Outer`.
+    Eq,
     /// `From<Inner> for Outer`.
     FromInner,
+    /// `std::str::FromStr for Outer`.
+    FromStr,
+    /// `std::hash::Hash for Outer`.
+    Hash,
     /// `From<Outer> for Arc<Outer>`.
     IntoArc,
     /// `From<Outer> for Box<Outer>`.
@@ -491,12 +521,26 @@ pub enum Derive {
     IntoInner,
     /// `From<Outer> for Rc<Outer>`.
     IntoRc,
+    /// `TryFrom<Inner> for Outer`.
+    TryFromInner,
+    /// `TryFrom<&Inner> for Outer`.
+    TryFromInnerRef,
     /// `std::fmt::LowerExp for Outer`.
     LowerExp,
+    /// `std::fmt::LowerExp for Outer`, formatting the deref target.
+    LowerExpDeref,
     /// `std::fmt::LowerHex for Outer`.
     LowerHex,
+    /// `std::fmt::LowerHex for Outer`, formatting the deref target.
+    LowerHexDeref,
     /// `std::fmt::Octal for Outer`.
     Octal,
+    /// `std::fmt::Octal for Outer`, formatting the deref target.
+    OctalDeref,
+    /// `PartialEq<DerefTarget> for Outer` and similar ones.
+    PartialEqDeref,
+    /// `PartialEq<Outer> for DerefTarget` and similar ones.
+    PartialEqDerefRev,
     /// `PartialEq<Inner> for Outer` and similar ones.
     PartialEqInner,
     /// `PartialEq<Outer> for Inner` and similar ones.
@@ -515,6 +559,10 @@ pub enum Derive {
     PartialEqSelfCowAndInner,
     /// `PartialEq<Inner> for Cow<Outer>` and similar ones.
     PartialEqSelfCowAndInnerRev,
+    /// `PartialOrd<DerefTarget> for Outer` and similar ones.
+    PartialOrdDeref,
+    /// `PartialOrd<Outer> for DerefTarget` and similar ones.
+    PartialOrdDerefRev,
     /// `PartialOrd<Inner> for Outer` and similar ones.
     PartialOrdInner,
     /// `PartialOrd<Outer> for Inner` and similar ones.
@@ -535,66 +583,99 @@ pub enum Derive {
     PartialOrdSelfCowAndInnerRev,
     /// `std::fmt::Pointer for Outer`.
     Pointer,
+    /// `std::fmt::Pointer for Outer`, formatting the deref target.
+    PointerDeref,
     /// `std::cmp::Ord for Outer`.
     Ord,
+    /// `serde::Serialize for Outer`.
+    Serialize,
     /// `std::fmt::UpperExp for Outer`.
     UpperExp,
+    /// `std::fmt::UpperExp for Outer`, formatting the deref target.
+    UpperExpDeref,
     /// `std::fmt::UpperHex for Outer`.
     UpperHex,
+    /// `std::fmt::UpperHex for Outer`, formatting the deref target.
+    UpperHexDeref,
 }
 
 impl Derive {
+    /// Returns derive targets implied by `#[opaque_typedef(cmp(key = ..))]`,
+    /// `#[opaque_typedef(cmp(ascii_case_insensitive))]`, and
+    /// `#[opaque_typedef(float(not_nan))]`.
+    pub fn implied_by_cmp_preset() -> &'static [Self] {
+        &[
+            Derive::PartialEqSelf,
+            Derive::Eq,
+            Derive::PartialOrdSelf,
+            Derive::Ord,
+            Derive::Hash,
+        ]
+    }
+
     /// Get derive targets from the given attributes.
-    pub fn from_attrs(attrs: &[syn::Attribute]) -> Vec<Self> {
+    ///
+    /// Derive targets are returned with their spans, and invalid targets are
+    /// reported to `errors`.
+    pub fn from_attrs(attrs: &[syn::Attribute], errors: &mut Vec<syn::Error>) -> Vec<(Self, Span)> {
+        Self::from_metas(&get_derive_meta(attrs), errors)
+    }
+
+    /// Get derive targets from the given meta items in
+    /// `#[opaque_typedef(derive(..))]` style.
+    ///
+    /// Derive targets are returned with their spans, and invalid targets are
+    /// reported to `errors`.
+    pub fn from_metas(metaitems: &[syn::Meta], errors: &mut Vec<syn::Error>) -> Vec<(Self, Span)> {
         let mut derives = Vec::new();
-        let metaitems = get_derive_meta(attrs);
-        for metaitem in &metaitems {
+        for metaitem in metaitems {
             match *metaitem {
-                syn::Meta::Word(ref ident) => {
-                    let target = ident.to_string();
-                    match target.parse::<Derive>() {
-                        Ok(v) => derives.push(v),
-                        Err(_) => {
-                            abort_on_unknown_derive_target(format_args!("{}", target));
-                        }
-                    }
-                }
+                syn::Meta::Word(ref ident) => match ident.to_string().parse::<Derive>() {
+                    Ok(v) => derives.push((v, ident.span())),
+                    Err(_) => errors.push(error_on_unknown_derive_target(
+                        ident,
+                        format_args!("{}", ident),
+                    )),
+                },
                 syn::Meta::List(ref metalist) => {
                     let parent = &metalist.ident;
                     for nested_meta in &metalist.nested {
                         let meta = match *nested_meta {
                             syn::NestedMeta::Meta(ref meta) => meta,
                             syn::NestedMeta::Literal(ref lit) => {
-                                abort_on_unsupported_derive_format(format_args!(
-                                    "{}({})",
-                                    parent,
-                                    lit.into_token_stream()
+                                errors.push(error_on_unsupported_derive_format(
+                                    lit,
+                                    format_args!("{}({})", parent, lit.into_token_stream()),
                                 ));
+                                continue;
                             }
                         };
                         match *meta {
                             syn::Meta::Word(ref ident) => Self::append_from_nested_names(
-                                &parent.to_string(),
-                                &[ident.to_string()],
+                                parent,
+                                &[ident],
                                 &mut derives,
+                                errors,
                             ),
-                            syn::Meta::List(ref metalist) => abort_on_unsupported_derive_format(
-                                format_args!("{}({})", parent, metalist.into_token_stream()),
-                            ),
+                            syn::Meta::List(ref metalist) => {
+                                errors.push(error_on_unsupported_derive_format(
+                                    metalist,
+                                    format_args!("{}({})", parent, metalist.into_token_stream()),
+                                ))
+                            }
                             syn::Meta::NameValue(ref namevalue) => {
-                                abort_on_unsupported_derive_format(format_args!(
-                                    "{}({})",
-                                    parent,
-                                    namevalue.into_token_stream()
+                                errors.push(error_on_unsupported_derive_format(
+                                    namevalue,
+                                    format_args!("{}({})", parent, namevalue.into_token_stream()),
                                 ))
                             }
                         }
                     }
                 }
                 syn::Meta::NameValue(ref namevalue) => {
-                    abort_on_unsupported_derive_format(format_args!(
-                        "{}",
-                        namevalue.into_token_stream()
+                    errors.push(error_on_unsupported_derive_format(
+                        namevalue,
+                        format_args!("{}", namevalue.into_token_stream()),
                     ));
                 }
             }
@@ -603,7 +684,12 @@ impl Derive {
     }
 
     /// Returns derive targets specified by `parent(child1, child2, ...)` style.
-    fn append_from_nested_names(parent: &str, children: &[String], derives: &mut Vec<Self>) {
+    fn append_from_nested_names(
+        parent: &syn::Ident,
+        children: &[&syn::Ident],
+        derives: &mut Vec<(Self, Span)>,
+        errors: &mut Vec<syn::Error>,
+    ) {
         lazy_static! {
             static ref NESTED_DERIVES: HashMap<&'static str, HashMap<&'static str, Derive>> = {
                 // NOTE: `Self_` is deprecated, but left here for compatibility reason.
@@ -621,13 +707,48 @@ impl Derive {
                         ("Self_", Derive::AsRefSelf),
                         ("Self", Derive::AsRefSelf),
                     ]),
+                    ("Binary", &[
+                        ("Deref", Derive::BinaryDeref),
+                    ]),
+                    ("Display", &[
+                        ("Deref", Derive::DisplayDeref),
+                    ]),
+                    ("LowerExp", &[
+                        ("Deref", Derive::LowerExpDeref),
+                    ]),
+                    ("LowerHex", &[
+                        ("Deref", Derive::LowerHexDeref),
+                    ]),
+                    ("Octal", &[
+                        ("Deref", Derive::OctalDeref),
+                    ]),
+                    ("Pointer", &[
+                        ("Deref", Derive::PointerDeref),
+                    ]),
+                    ("UpperExp", &[
+                        ("Deref", Derive::UpperExpDeref),
+                    ]),
+                    ("UpperHex", &[
+                        ("Deref", Derive::UpperHexDeref),
+                    ]),
+                    ("Debug", &[
+                        ("Deref", Derive::DebugDeref),
+                        ("Named", Derive::DebugNamed),
+                        ("Transparent", Derive::DebugTransparent),
+                    ]),
                     ("Into", &[
                         ("Arc", Derive::IntoArc),
                         ("Box", Derive::IntoBox),
                         ("Inner", Derive::IntoInner),
                         ("Rc", Derive::IntoRc),
                     ]),
+                    ("TryFrom", &[
+                        ("Inner", Derive::TryFromInner),
+                        ("InnerRef", Derive::TryFromInnerRef),
+                    ]),
                     ("PartialEq", &[
+                        ("Deref", Derive::PartialEqDeref),
+                        ("DerefRev", Derive::PartialEqDerefRev),
                         ("Inner", Derive::PartialEqInner),
                         ("InnerRev", Derive::PartialEqInnerRev),
                         ("InnerCow", Derive::PartialEqInnerCow),
@@ -640,6 +761,8 @@ impl Derive {
                         ("SelfCowAndInnerRev", Derive::PartialEqSelfCowAndInnerRev),
                     ]),
                     ("PartialOrd", &[
+                        ("Deref", Derive::PartialOrdDeref),
+                        ("DerefRev", Derive::PartialOrdDerefRev),
                         ("Inner", Derive::PartialOrdInner),
                         ("InnerRev", Derive::PartialOrdInnerRev),
                         ("InnerCow", Derive::PartialOrdInnerCow),
@@ -878,19 +1001,31 @@ impl Derive {
             };
         }
 
-        let submap = NESTED_DERIVES.get(parent).unwrap_or_else(|| {
-            abort_on_unknown_derive_target(format_args!("{}(..)", parent));
-        });
-        derives.extend(children.iter().map(|child| {
-            submap.get(child.as_str()).unwrap_or_else(|| {
-                abort_on_unknown_derive_target(format_args!("{}({})", parent, child));
-            })
-        }));
+        let submap = match NESTED_DERIVES.get(parent.to_string().as_str()) {
+            Some(v) => v,
+            None => {
+                errors.push(error_on_unknown_derive_target(
+                    parent,
+                    format_args!("{}(..)", parent),
+                ));
+                return;
+            }
+        };
+        for child in children {
+            match submap.get(child.to_string().as_str()) {
+                Some(&target) => derives.push((target, child.span())),
+                None => errors.push(error_on_unknown_derive_target(
+                    child,
+                    format_args!("{}({})", parent, child),
+                )),
+            }
+        }
     }
 
     /// Generates impls for the auto-derive target.
-    pub fn impl_auto_derive(self, props: &TypeProps) -> TokenStream {
-        match (self, props.inner_sizedness) {
+    pub fn impl_auto_derive(self, props: &TypeProps) -> syn::Result<TokenStream> {
+        secret::check_derive(self, props)?;
+        let tokens = match (self, props.inner_sizedness) {
             // `std::fmt::*` traits.
             (Derive::Binary, _)
             | (Derive::Display, _)
@@ -899,45 +1034,79 @@ impl Derive {
             | (Derive::Octal, _)
             | (Derive::Pointer, _)
             | (Derive::UpperExp, _)
-            | (Derive::UpperHex, _) => fmt::gen_impl(self, props),
+            | (Derive::UpperHex, _)
+            | (Derive::BinaryDeref, _)
+            | (Derive::DisplayDeref, _)
+            | (Derive::LowerExpDeref, _)
+            | (Derive::LowerHexDeref, _)
+            | (Derive::OctalDeref, _)
+            | (Derive::PointerDeref, _)
+            | (Derive::UpperExpDeref, _)
+            | (Derive::UpperHexDeref, _) => fmt::gen_impl(self, props)?,
+            (Derive::DebugDeref, _) | (Derive::DebugNamed, _) | (Derive::DebugTransparent, _) => {
+                fmt::gen_impl_debug(self, props)?
+            }
             // `std::ops::Deref*` traits.
-            (Derive::Deref, _) | (Derive::DerefMut, _) => deref::gen_impl(self, props),
+            (Derive::Deref, _) | (Derive::DerefMut, _) => deref::gen_impl(self, props)?,
             // `std::conevert::As*` traits.
             (Derive::AsMutDeref, _)
             | (Derive::AsMutInner, _)
             | (Derive::AsMutSelf, _)
             | (Derive::AsRefDeref, _)
             | (Derive::AsRefInner, _)
-            | (Derive::AsRefSelf, _) => as_ref::gen_impl(self, props),
+            | (Derive::AsRefSelf, _) => as_ref::gen_impl(self, props)?,
             // `std::convert::{From, Into}` traits.
             (Derive::FromInner, _) => convert::gen_impl_from_inner(props),
             (Derive::IntoArc, _) | (Derive::IntoBox, _) | (Derive::IntoRc, _) => {
                 match props.inner_sizedness {
-                    Sizedness::Sized => panic!(
-                        "`#[opaque_typedef(derive({}))]` is not supported for sized types",
-                        self.as_ref()
-                    ),
-                    Sizedness::Unsized => convert::gen_impl_into_smartptr(self, props),
+                    Sizedness::Sized => return Err(self.error_unsupported_for_sized(props)),
+                    Sizedness::Unsized => convert::gen_impl_into_smartptr(self, props)?,
                 }
             }
             (Derive::IntoInner, _) => convert::gen_impl_into_inner(props),
+            // `std::str::FromStr` trait.
+            (Derive::FromStr, Sizedness::Sized) => convert::gen_impl_from_str(props)?,
+            (Derive::FromStr, Sizedness::Unsized) => {
+                return Err(self.error_unsupported_for_unsized(props));
+            }
+            (Derive::TryFromInner, _) => {
+                if props.derives.contains(&Derive::FromInner) {
+                    return Err(syn::Error::new(
+                        props.derive_span(self),
+                        format!(
+                            "`#[opaque_typedef(derive({}))]` cannot be used with \
+                             `#[opaque_typedef(derive(FromInner))]`, because `TryFrom` is \
+                             automatically implemented for types with `From` impls",
+                            self.as_ref()
+                        ),
+                    ));
+                }
+                convert::gen_impl_try_from_inner(props)
+            }
+            (Derive::TryFromInnerRef, Sizedness::Sized) => {
+                convert::gen_impl_try_from_inner_ref(props)
+            }
+            (Derive::TryFromInnerRef, Sizedness::Unsized) => {
+                return Err(self.error_unsupported_for_unsized(props));
+            }
             // `std::default::Default` trait.
-            (Derive::DefaultRef, Sizedness::Sized) => panic!(
-                "`#[opaque_typedef(derive({}))]` is not supported for sized types",
-                self.as_ref()
-            ),
+            (Derive::DefaultRef, Sizedness::Sized) => {
+                return Err(self.error_unsupported_for_sized(props));
+            }
             (Derive::DefaultRef, Sizedness::Unsized) => {
+                let core = paths::core();
                 let ty_outer = props.ty_outer.into_token_stream();
                 let type_generics = &props.type_generics;
                 let (generics, new_lifetimes) =
-                    extend_generics(Cow::Borrowed(props.generics), 1, &[]);
+                    extend_generics(props.generics_with_helper_trait_preds(), 1, &[]);
                 let new_lt = &new_lifetimes[0];
                 let ty_inner = props.field_inner.ty().into_token_stream();
                 let extra_preds = if props.has_type_params() {
                     let pred = syn::parse_str::<syn::WherePredicate>(&format!(
-                        "&{} {}: ::std::default::Default",
+                        "&{} {}: {}::default::Default",
                         new_lt.into_token_stream(),
                         ty_inner,
+                        core,
                     ))
                     .expect("Failed to generate `WherePredicate`");
                     vec![pred]
@@ -949,18 +1118,20 @@ impl Derive {
                 let helper_trait = props.helper_trait();
                 quote! {
                     impl #impl_generics
-                        ::std::default::Default for &#new_lt #ty_outer #type_generics
+                        #core::default::Default for &#new_lt #ty_outer #type_generics
                     #where_clause
                     {
                         fn default() -> Self {
-                            let inner = <&#new_lt #ty_inner as ::std::default::Default>::default();
+                            let inner = <&#new_lt #ty_inner as #core::default::Default>::default();
                             <#ty_outer #type_generics as #helper_trait>::from_inner(inner)
                         }
                     }
                 }
             }
             // `std::cmp::Partial{Eq,Ord}` traits.
-            (Derive::PartialEqInner, _)
+            (Derive::PartialEqDeref, _)
+            | (Derive::PartialEqDerefRev, _)
+            | (Derive::PartialEqInner, _)
             | (Derive::PartialEqInnerRev, _)
             | (Derive::PartialEqInnerCow, Sizedness::Unsized)
             | (Derive::PartialEqInnerCowRev, Sizedness::Unsized)
@@ -969,6 +1140,8 @@ impl Derive {
             | (Derive::PartialEqSelfCowRev, Sizedness::Unsized)
             | (Derive::PartialEqSelfCowAndInner, Sizedness::Unsized)
             | (Derive::PartialEqSelfCowAndInnerRev, Sizedness::Unsized)
+            | (Derive::PartialOrdDeref, _)
+            | (Derive::PartialOrdDerefRev, _)
             | (Derive::PartialOrdInner, _)
             | (Derive::PartialOrdInnerRev, _)
             | (Derive::PartialOrdInnerCow, Sizedness::Unsized)
@@ -978,7 +1151,7 @@ impl Derive {
             | (Derive::PartialOrdSelfCowRev, Sizedness::Unsized)
             | (Derive::PartialOrdSelfCowAndInner, Sizedness::Unsized)
             | (Derive::PartialOrdSelfCowAndInnerRev, Sizedness::Unsized) => {
-                cmp::gen_impl_partial_cmp(self, props)
+                cmp::gen_impl_partial_cmp(self, props)?
             }
             (Derive::PartialEqInnerCow, Sizedness::Sized)
             | (Derive::PartialEqInnerCowRev, Sizedness::Sized)
@@ -991,33 +1164,39 @@ impl Derive {
             | (Derive::PartialOrdSelfCow, Sizedness::Sized)
             | (Derive::PartialOrdSelfCowRev, Sizedness::Sized)
             | (Derive::PartialOrdSelfCowAndInner, Sizedness::Sized)
-            | (Derive::PartialOrdSelfCowAndInnerRev, Sizedness::Sized) => panic!(
-                "`#[opaque_typedef(derive({}))]` is not supported for sized types",
-                self.as_ref()
-            ),
+            | (Derive::PartialOrdSelfCowAndInnerRev, Sizedness::Sized) => {
+                return Err(self.error_unsupported_for_sized(props));
+            }
+            // `std::cmp::Eq` trait.
+            (Derive::Eq, _) => cmp::gen_impl_eq(props),
             // `std::cmp::Ord` trait.
-            (Derive::Ord, _) => cmp::gen_impl_ord(props),
+            (Derive::Ord, _) => cmp::gen_impl_ord(props)?,
+            // `std::hash::Hash` trait.
+            (Derive::Hash, _) => cmp::gen_impl_hash(props)?,
+            // `serde::{Serialize, Deserialize}` traits.
+            (Derive::Serialize, _) => serde::gen_impl_serialize(props),
+            (Derive::Deserialize, _) => serde::gen_impl_deserialize(props),
             // `std::ascii::AsciiExt` trait.
             (Derive::AsciiExt, _) => {
+                let std = paths::std(
+                    props.derive_span(self),
+                    format_args!("`#[opaque_typedef(derive({}))]`", self.as_ref()),
+                )?;
                 let ty_outer = &props.ty_outer;
                 let type_generics = &props.type_generics;
                 let ty_inner = props.field_inner.ty();
-                let ty_inner_as_asciiext = quote!(<#ty_inner as ::std::ascii::AsciiExt>);
+                let ty_inner_as_asciiext = quote!(<#ty_inner as #std::ascii::AsciiExt>);
                 let self_as_inner = props.tokens_outer_expr_as_inner(quote!(self));
                 let other_as_inner = props.tokens_outer_expr_as_inner(quote!(other));
                 if !props.is_mut_ref_allowed {
-                    panic!(
-                        "`#[opaque_typedef(derive({}))]` requires \
-                         `#[opaque_typedef(allow_mut_ref)]`, but not specified",
-                        self.as_ref()
-                    );
+                    return Err(self.error_mut_ref_not_allowed(props));
                 }
                 let self_as_inner_mut = props.tokens_outer_expr_as_inner_mut(quote!(self));
                 let extra_preds = if props.has_type_params() {
                     let ty_inner = ty_inner.into_token_stream();
                     let pred = syn::parse_str::<syn::WherePredicate>(&format!(
-                        "{}: ::std::ascii::AsciiExt",
-                        ty_inner
+                        "{}: {}::ascii::AsciiExt",
+                        ty_inner, std
                     ))
                     .expect("Failed to generate `WherePredicate`");
                     vec![pred]
@@ -1027,7 +1206,7 @@ impl Derive {
                 let (generics, _) = extend_generics(Cow::Borrowed(props.generics), 0, &extra_preds);
                 let (impl_generics, _, where_clause) = generics.split_for_impl();
                 quote! {
-                    impl #impl_generics ::std::ascii::AsciiExt for #ty_outer #type_generics
+                    impl #impl_generics #std::ascii::AsciiExt for #ty_outer #type_generics
                     #where_clause
                     {
                         type Owned = #ty_inner_as_asciiext::Owned;
@@ -1058,8 +1237,8 @@ impl Derive {
             // Simple operators.
             _ => match OpSpec::from_derive_target(self) {
                 Some(op_spec) => match props.inner_sizedness {
-                    Sizedness::Sized => op_spec.gen_impl_sized(props, self),
-                    Sizedness::Unsized => op_spec.gen_impl_unsized(props, self),
+                    Sizedness::Sized => op_spec.gen_impl_sized(props, self)?,
+                    Sizedness::Unsized => op_spec.gen_impl_unsized(props, self)?,
                 },
                 None => {
                     panic!(
@@ -1069,7 +1248,42 @@ impl Derive {
                     );
                 }
             },
-        }
+        };
+        Ok(tokens)
+    }
+
+    /// Returns an error for the target which is not supported for sized types.
+    pub fn error_unsupported_for_sized(self, props: &TypeProps) -> syn::Error {
+        syn::Error::new(
+            props.derive_span(self),
+            format!(
+                "`#[opaque_typedef(derive({}))]` is not supported for sized types",
+                self.as_ref()
+            ),
+        )
+    }
+
+    /// Returns an error for the target which is not supported for unsized types.
+    pub fn error_unsupported_for_unsized(self, props: &TypeProps) -> syn::Error {
+        syn::Error::new(
+            props.derive_span(self),
+            format!(
+                "`#[opaque_typedef(derive({}))]` is not supported for unsized types",
+                self.as_ref()
+            ),
+        )
+    }
+
+    /// Returns an error for the target which requires `allow_mut_ref`.
+    pub fn error_mut_ref_not_allowed(self, props: &TypeProps) -> syn::Error {
+        syn::Error::new(
+            props.derive_span(self),
+            format!(
+                "`#[opaque_typedef(derive({}))]` requires \
+                 `#[opaque_typedef(allow_mut_ref)]`, but not specified",
+                self.as_ref()
+            ),
+        )
     }
 }
 
@@ -1087,16 +1301,28 @@ fn get_derive_meta(attrs: &[syn::Attribute]) -> Vec<syn::Meta> {
         .collect()
 }
 
-fn abort_on_unknown_derive_target(target: ::std::fmt::Arguments) -> ! {
-    panic!(
-        "`#[opaque_typedef(derive({target}))]` is specified, but the target `{target}` is unknown",
-        target = target
-    );
+fn error_on_unknown_derive_target<T: ToTokens>(
+    tokens: T,
+    target: ::std::fmt::Arguments,
+) -> syn::Error {
+    syn::Error::new_spanned(
+        tokens,
+        format!(
+            "`#[opaque_typedef(derive({target}))]` is specified, but the target `{target}` is unknown",
+            target = target
+        ),
+    )
 }
 
-fn abort_on_unsupported_derive_format(inner: ::std::fmt::Arguments) -> ! {
-    panic!(
-        "`#[opaque_typedef(derive({}))]` is specified, but this format is not supported",
-        inner
-    );
+fn error_on_unsupported_derive_format<T: ToTokens>(
+    tokens: T,
+    inner: ::std::fmt::Arguments,
+) -> syn::Error {
+    syn::Error::new_spanned(
+        tokens,
+        format!(
+            "`#[opaque_typedef(derive({}))]` is specified, but this format is not supported",
+            inner
+        ),
+    )
 }
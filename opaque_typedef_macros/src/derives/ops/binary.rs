@@ -6,25 +6,36 @@ use proc_macro2::TokenStream;
 use quote::ToTokens;
 use syn;
 
-use type_props::TypeProps;
+use derives::Derive;
+use paths;
+use type_props::{AssignOpsStrategy, OnInvalidOpResult, TypeProps};
 use utils::extend_generics;
 
-use super::{OperandSpec, OperandTypeSpec, OperandTypeWrapperSpec};
+use super::{
+    tokens_outer_from_result, tokens_unsized_output, tokens_unsized_output_binding, OperandSpec,
+    OperandTypeSpec, OperandTypeWrapperSpec,
+};
 
 /// Binary operations.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumString, EnumProperty)]
 pub enum BinOpSpec {
     /// `std::ops::Add`.
     #[strum(props(
-        trait_ = "::std::ops::Add",
+        trait_ = "ops::Add",
         method = "add",
         self_ = "self",
-        ty_ret = "Self::Output"
+        ty_ret = "Self::Output",
+        try_trait_ = "ops::TryAdd",
+        try_method = "try_add",
+        saturating_trait_ = "ops::SaturatingAdd",
+        saturating_method = "saturating_add",
+        wrapping_trait_ = "ops::WrappingAdd",
+        wrapping_method = "wrapping_add"
     ))]
     Add,
     /// `std::ops::AddAssign`.
     #[strum(props(
-        trait_ = "::std::ops::AddAssign",
+        trait_ = "ops::AddAssign",
         method = "add_assign",
         self_ = "&mut self",
         ty_ret = "()"
@@ -32,15 +43,17 @@ pub enum BinOpSpec {
     AddAssign,
     /// `std::ops::BitAnd`.
     #[strum(props(
-        trait_ = "::std::ops::BitAnd",
+        trait_ = "ops::BitAnd",
         method = "bitand",
         self_ = "self",
-        ty_ret = "Self::Output"
+        ty_ret = "Self::Output",
+        try_trait_ = "ops::TryBitAnd",
+        try_method = "try_bitand"
     ))]
     BitAnd,
     /// `std::ops::AddAssign`.
     #[strum(props(
-        trait_ = "::std::ops::BitAndAssign",
+        trait_ = "ops::BitAndAssign",
         method = "bitand_assign",
         self_ = "&mut self",
         ty_ret = "()"
@@ -48,15 +61,17 @@ pub enum BinOpSpec {
     BitAndAssign,
     /// `std::ops::BitOr`.
     #[strum(props(
-        trait_ = "::std::ops::BitOr",
+        trait_ = "ops::BitOr",
         method = "bitor",
         self_ = "self",
-        ty_ret = "Self::Output"
+        ty_ret = "Self::Output",
+        try_trait_ = "ops::TryBitOr",
+        try_method = "try_bitor"
     ))]
     BitOr,
     /// `std::ops::AddAssign`.
     #[strum(props(
-        trait_ = "::std::ops::BitOrAssign",
+        trait_ = "ops::BitOrAssign",
         method = "bitor_assign",
         self_ = "&mut self",
         ty_ret = "()"
@@ -64,15 +79,17 @@ pub enum BinOpSpec {
     BitOrAssign,
     /// `std::ops::BitXor`.
     #[strum(props(
-        trait_ = "::std::ops::BitXor",
+        trait_ = "ops::BitXor",
         method = "bitxor",
         self_ = "self",
-        ty_ret = "Self::Output"
+        ty_ret = "Self::Output",
+        try_trait_ = "ops::TryBitXor",
+        try_method = "try_bitxor"
     ))]
     BitXor,
     /// `std::ops::AddAssign`.
     #[strum(props(
-        trait_ = "::std::ops::BitXorAssign",
+        trait_ = "ops::BitXorAssign",
         method = "bitxor_assign",
         self_ = "&mut self",
         ty_ret = "()"
@@ -80,15 +97,21 @@ pub enum BinOpSpec {
     BitXorAssign,
     /// `std::ops::Div`.
     #[strum(props(
-        trait_ = "::std::ops::Div",
+        trait_ = "ops::Div",
         method = "div",
         self_ = "self",
-        ty_ret = "Self::Output"
+        ty_ret = "Self::Output",
+        try_trait_ = "ops::TryDiv",
+        try_method = "try_div",
+        saturating_trait_ = "ops::SaturatingDiv",
+        saturating_method = "saturating_div",
+        wrapping_trait_ = "ops::WrappingDiv",
+        wrapping_method = "wrapping_div"
     ))]
     Div,
     /// `std::ops::DivAssign`.
     #[strum(props(
-        trait_ = "::std::ops::DivAssign",
+        trait_ = "ops::DivAssign",
         method = "div_assign",
         self_ = "&mut self",
         ty_ret = "()"
@@ -96,15 +119,21 @@ pub enum BinOpSpec {
     DivAssign,
     /// `std::ops::Mul`.
     #[strum(props(
-        trait_ = "::std::ops::Mul",
+        trait_ = "ops::Mul",
         method = "mul",
         self_ = "self",
-        ty_ret = "Self::Output"
+        ty_ret = "Self::Output",
+        try_trait_ = "ops::TryMul",
+        try_method = "try_mul",
+        saturating_trait_ = "ops::SaturatingMul",
+        saturating_method = "saturating_mul",
+        wrapping_trait_ = "ops::WrappingMul",
+        wrapping_method = "wrapping_mul"
     ))]
     Mul,
     /// `std::ops::MulAssign`.
     #[strum(props(
-        trait_ = "::std::ops::MulAssign",
+        trait_ = "ops::MulAssign",
         method = "mul_assign",
         self_ = "&mut self",
         ty_ret = "()"
@@ -112,15 +141,19 @@ pub enum BinOpSpec {
     MulAssign,
     /// `std::ops::Rem`.
     #[strum(props(
-        trait_ = "::std::ops::Rem",
+        trait_ = "ops::Rem",
         method = "rem",
         self_ = "self",
-        ty_ret = "Self::Output"
+        ty_ret = "Self::Output",
+        try_trait_ = "ops::TryRem",
+        try_method = "try_rem",
+        wrapping_trait_ = "ops::WrappingRem",
+        wrapping_method = "wrapping_rem"
     ))]
     Rem,
     /// `std::ops::RemAssign`.
     #[strum(props(
-        trait_ = "::std::ops::RemAssign",
+        trait_ = "ops::RemAssign",
         method = "rem_assign",
         self_ = "&mut self",
         ty_ret = "()"
@@ -128,15 +161,19 @@ pub enum BinOpSpec {
     RemAssign,
     /// `std::ops::Shl`.
     #[strum(props(
-        trait_ = "::std::ops::Shl",
+        trait_ = "ops::Shl",
         method = "shl",
         self_ = "self",
-        ty_ret = "Self::Output"
+        ty_ret = "Self::Output",
+        try_trait_ = "ops::TryShl",
+        try_method = "try_shl",
+        wrapping_trait_ = "ops::WrappingShl",
+        wrapping_method = "wrapping_shl"
     ))]
     Shl,
     /// `std::ops::ShlAssign`.
     #[strum(props(
-        trait_ = "::std::ops::ShlAssign",
+        trait_ = "ops::ShlAssign",
         method = "shl_assign",
         self_ = "&mut self",
         ty_ret = "()"
@@ -144,15 +181,19 @@ pub enum BinOpSpec {
     ShlAssign,
     /// `std::ops::Shr`.
     #[strum(props(
-        trait_ = "::std::ops::Shr",
+        trait_ = "ops::Shr",
         method = "shr",
         self_ = "self",
-        ty_ret = "Self::Output"
+        ty_ret = "Self::Output",
+        try_trait_ = "ops::TryShr",
+        try_method = "try_shr",
+        wrapping_trait_ = "ops::WrappingShr",
+        wrapping_method = "wrapping_shr"
     ))]
     Shr,
     /// `std::ops::ShrAssign`.
     #[strum(props(
-        trait_ = "::std::ops::ShrAssign",
+        trait_ = "ops::ShrAssign",
         method = "shr_assign",
         self_ = "&mut self",
         ty_ret = "()"
@@ -160,15 +201,21 @@ pub enum BinOpSpec {
     ShrAssign,
     /// `std::ops::Sub`.
     #[strum(props(
-        trait_ = "::std::ops::Sub",
+        trait_ = "ops::Sub",
         method = "sub",
         self_ = "self",
-        ty_ret = "Self::Output"
+        ty_ret = "Self::Output",
+        try_trait_ = "ops::TrySub",
+        try_method = "try_sub",
+        saturating_trait_ = "ops::SaturatingSub",
+        saturating_method = "saturating_sub",
+        wrapping_trait_ = "ops::WrappingSub",
+        wrapping_method = "wrapping_sub"
     ))]
     Sub,
     /// `std::ops::SubAssign`.
     #[strum(props(
-        trait_ = "::std::ops::SubAssign",
+        trait_ = "ops::SubAssign",
         method = "sub_assign",
         self_ = "&mut self",
         ty_ret = "()"
@@ -196,7 +243,9 @@ impl BinOpSpec {
 
     /// Returns target trait path.
     pub fn tokens_trait_path(self) -> TokenStream {
-        self.parse_prop::<syn::Path>("trait_").into_token_stream()
+        let core = paths::core();
+        let trait_ = self.parse_prop::<syn::Path>("trait_");
+        quote!(#core::#trait_)
     }
 
     /// Returns method name to implement.
@@ -262,12 +311,13 @@ impl BinOpSpec {
         self.parse_prop::<syn::Type>("ty_ret").into_token_stream()
     }
 
-    // TODO: Make it configurable to choose inner implementation of `*Assign`.
-    // In other words, users should be able to select `a = a + b;` style or
-    // `a.inner += b.inner;` style, for each `*Assign` trait impls.
-    // Note that this feature also requires trait bounds generation to be
-    // configurable.
-    pub fn tokens_from_inner_result<T, U>(self, ty_outer: T, helper_trait: U) -> TokenStream
+    /// Returns the expression to be returned from the operator method.
+    pub fn tokens_from_inner_result<T, U>(
+        self,
+        props: &TypeProps,
+        ty_outer: T,
+        result: U,
+    ) -> TokenStream
     where
         T: ToTokens,
         U: ToTokens,
@@ -282,7 +332,7 @@ impl BinOpSpec {
             | BinOpSpec::Rem
             | BinOpSpec::Shl
             | BinOpSpec::Shr
-            | BinOpSpec::Sub => quote!(<#ty_outer as #helper_trait>::from_inner),
+            | BinOpSpec::Sub => tokens_outer_from_result(props, ty_outer, result),
             BinOpSpec::AddAssign
             | BinOpSpec::BitAndAssign
             | BinOpSpec::BitOrAssign
@@ -292,7 +342,80 @@ impl BinOpSpec {
             | BinOpSpec::RemAssign
             | BinOpSpec::ShlAssign
             | BinOpSpec::ShrAssign
-            | BinOpSpec::SubAssign => quote!(),
+            | BinOpSpec::SubAssign => result.into_token_stream(),
+        }
+    }
+
+    /// Returns the trait path and the method to operate on the inner values.
+    ///
+    /// For `#[opaque_typedef(ops(on_invalid = "saturate" | "wrap"))]`, the
+    /// saturating or wrapping operator is used if available.
+    pub fn tokens_inner_op(self, on_invalid: &OnInvalidOpResult) -> (TokenStream, TokenStream) {
+        use strum::EnumProperty;
+
+        let (trait_prop, method_prop) = match *on_invalid {
+            OnInvalidOpResult::Saturate(_) => ("saturating_trait_", "saturating_method"),
+            OnInvalidOpResult::Wrap(_) => ("wrapping_trait_", "wrapping_method"),
+            OnInvalidOpResult::Panic | OnInvalidOpResult::Checked => {
+                return (self.tokens_trait_path(), self.tokens_method());
+            }
+        };
+        if self.get_str(trait_prop).is_none() {
+            return (self.tokens_trait_path(), self.tokens_method());
+        }
+        let trait_ = self.parse_prop::<syn::Path>(trait_prop);
+        let method = self.parse_prop::<syn::Ident>(method_prop);
+        (
+            quote!(::opaque_typedef::#trait_),
+            method.into_token_stream(),
+        )
+    }
+
+    /// Returns fallible trait path, if available.
+    pub fn tokens_try_trait_path(self) -> Option<TokenStream> {
+        use strum::EnumProperty;
+
+        self.get_str("try_trait_")?;
+        let trait_ = self.parse_prop::<syn::Path>("try_trait_");
+        Some(quote!(::opaque_typedef::#trait_))
+    }
+
+    /// Returns fallible method name to implement, if available.
+    pub fn tokens_try_method(self) -> Option<TokenStream> {
+        use strum::EnumProperty;
+
+        self.get_str("try_method")?;
+        Some(
+            self.parse_prop::<syn::Ident>("try_method")
+                .into_token_stream(),
+        )
+    }
+
+    /// Returns the non-assign operator corresponding to the assign operator.
+    ///
+    /// Returns `None` if the operator is not an assign operator.
+    pub fn binop_for_assign(self) -> Option<BinOpSpec> {
+        match self {
+            BinOpSpec::AddAssign => Some(BinOpSpec::Add),
+            BinOpSpec::BitAndAssign => Some(BinOpSpec::BitAnd),
+            BinOpSpec::BitOrAssign => Some(BinOpSpec::BitOr),
+            BinOpSpec::BitXorAssign => Some(BinOpSpec::BitXor),
+            BinOpSpec::DivAssign => Some(BinOpSpec::Div),
+            BinOpSpec::MulAssign => Some(BinOpSpec::Mul),
+            BinOpSpec::RemAssign => Some(BinOpSpec::Rem),
+            BinOpSpec::ShlAssign => Some(BinOpSpec::Shl),
+            BinOpSpec::ShrAssign => Some(BinOpSpec::Shr),
+            BinOpSpec::SubAssign => Some(BinOpSpec::Sub),
+            BinOpSpec::Add
+            | BinOpSpec::BitAnd
+            | BinOpSpec::BitOr
+            | BinOpSpec::BitXor
+            | BinOpSpec::Div
+            | BinOpSpec::Mul
+            | BinOpSpec::Rem
+            | BinOpSpec::Shl
+            | BinOpSpec::Shr
+            | BinOpSpec::Sub => None,
         }
     }
 
@@ -467,7 +590,7 @@ pub fn gen_impl_sized(
         let num_extra_lts_lhs = lhs_spec.num_required_extra_lifetimes();
         let num_extra_lts_rhs = rhs_spec.num_required_extra_lifetimes();
         let num_extra_lts = num_extra_lts_lhs + num_extra_lts_rhs;
-        let generics = Cow::Borrowed(props.generics);
+        let generics = props.generics_with_helper_trait_preds();
         let (generics, new_lts) = if num_extra_lts > 0 {
             extend_generics(generics, num_extra_lts, &[])
         } else {
@@ -490,22 +613,48 @@ pub fn gen_impl_sized(
         )
     };
 
+    // Non-assign operator to be used to implement the assign operator.
+    let binop_for_assign = match props.ops_spec.assign_ops {
+        AssignOpsStrategy::ViaBinop => op_spec.binop_for_assign(),
+        AssignOpsStrategy::InPlace => None,
+    };
+
+    // Operator on the inner values.
+    let (inner_trait, inner_method) = op_spec.tokens_inner_op(&props.ops_spec.on_invalid);
+
     let (generics, _) = {
         let extra_preds = if props.has_type_params() {
             let associated_ty_output = op_spec.tokens_associated_ty_output(&ty_outer_generic);
-            let pred_str = match associated_ty_output {
-                Some(_) => format!(
+            let pred_strs = match (associated_ty_output, binop_for_assign) {
+                (Some(_), _) => vec![format!(
                     "{}: {}<{}, Output={}>",
                     ty_lhs_inner,
-                    target_trait,
+                    inner_trait,
                     ty_rhs_inner,
                     ty_inner.into_token_stream()
-                ),
-                None => format!("{}: {}<{}>", ty_lhs_inner, target_trait, ty_rhs_inner),
+                )],
+                (None, Some(binop)) => vec![
+                    format!("{}: {}::clone::Clone", ty_lhs_inner, paths::core()),
+                    format!(
+                        "{}: {}<{}, Output={}>",
+                        ty_lhs_inner,
+                        binop.tokens_inner_op(&props.ops_spec.on_invalid).0,
+                        ty_rhs_inner,
+                        ty_inner.into_token_stream()
+                    ),
+                ],
+                (None, None) => vec![format!(
+                    "{}: {}<{}>",
+                    ty_lhs_inner, target_trait, ty_rhs_inner
+                )],
             };
-            let pred = syn::parse_str::<syn::WherePredicate>(&pred_str)
-                .expect("Failed to generate `WherePredicate`");
-            vec![pred]
+            pred_strs
+                .iter()
+                .map(|pred_str| {
+                    syn::parse_str::<syn::WherePredicate>(pred_str)
+                        .expect("Failed to generate `WherePredicate`")
+                })
+                .collect()
         } else {
             Vec::new()
         };
@@ -516,28 +665,220 @@ pub fn gen_impl_sized(
     let other = quote!(other);
     let lhs_self_arg = op_spec.tokens_arg_self();
     let ty_rhs_arg = op_spec.tokens_ty_rhs_arg(&ty_rhs_impl);
-    let associated = op_spec.tokens_associated_stuff(&ty_outer_generic);
     let method_name = op_spec.tokens_method();
-    let ty_ret = op_spec.tokens_ty_ret();
-    let self_inner = op_spec.tokens_lhs_inner_arg(props, lhs_spec);
     let other_inner = op_spec.tokens_rhs_inner_arg(props, rhs_spec, &other);
-    let from_inner_result =
-        op_spec.tokens_from_inner_result(&ty_outer_generic, props.helper_trait());
+    // Fallible trait to be implemented instead of `target_trait`.
+    let try_trait = match props.ops_spec.on_invalid {
+        OnInvalidOpResult::Checked => op_spec
+            .tokens_try_trait_path()
+            .and_then(|path| op_spec.tokens_try_method().map(|method| (path, method))),
+        _ => None,
+    };
+    let (impl_trait, impl_method, associated, ty_ret) = match try_trait {
+        Some((try_trait, try_method)) => {
+            let helper_trait = props.helper_trait();
+            let core = paths::core();
+            (
+                try_trait,
+                try_method,
+                quote! {
+                    type Output = #ty_outer_generic;
+                    type Error = <#ty_outer_generic as #helper_trait>::Error;
+                },
+                quote!(#core::result::Result<Self::Output, Self::Error>),
+            )
+        }
+        None => (
+            target_trait.clone(),
+            method_name.clone(),
+            op_spec.tokens_associated_stuff(&ty_outer_generic),
+            op_spec.tokens_ty_ret(),
+        ),
+    };
+    let body = match binop_for_assign {
+        Some(binop) => {
+            let core = paths::core();
+            let (binop_trait, binop_method) = binop.tokens_inner_op(&props.ops_spec.on_invalid);
+            let self_inner = props.tokens_outer_expr_as_inner(quote!(self));
+            let outer = tokens_outer_from_result(props, &ty_outer_generic, quote!(__result));
+            quote! {
+                let __result = #binop_trait::#binop_method(
+                    #core::clone::Clone::clone(#self_inner),
+                    #other_inner
+                );
+                *self = #outer;
+            }
+        }
+        None => {
+            let self_inner = op_spec.tokens_lhs_inner_arg(props, lhs_spec);
+            op_spec.tokens_from_inner_result(
+                props,
+                &ty_outer_generic,
+                quote! {
+                    #inner_trait::#inner_method(
+                        #self_inner,
+                        #other_inner
+                    )
+                },
+            )
+        }
+    };
 
     quote! {
-        impl #impl_generics #target_trait<#ty_rhs_impl> for #ty_lhs_impl
+        impl #impl_generics #impl_trait<#ty_rhs_impl> for #ty_lhs_impl
         #where_clause
         {
             #associated
 
-            fn #method_name(#lhs_self_arg, #other: #ty_rhs_arg) -> #ty_ret {
-                #from_inner_result(
-                    #target_trait::#method_name(
-                        #self_inner,
-                        #other_inner
-                    )
-                )
+            fn #impl_method(#lhs_self_arg, #other: #ty_rhs_arg) -> #ty_ret {
+                #body
             }
         }
     }
 }
+
+pub fn gen_impl_unsized_ref(
+    props: &TypeProps,
+    op_spec: BinOpSpec,
+    lhs_spec: OperandTypeSpec,
+    rhs_spec: OperandTypeSpec,
+    target: Derive,
+) -> syn::Result<TokenStream> {
+    assert!(lhs_spec != OperandTypeSpec::Inner || rhs_spec != OperandTypeSpec::Inner);
+    match op_spec {
+        BinOpSpec::Add
+        | BinOpSpec::BitAnd
+        | BinOpSpec::BitOr
+        | BinOpSpec::BitXor
+        | BinOpSpec::Div
+        | BinOpSpec::Mul
+        | BinOpSpec::Rem
+        | BinOpSpec::Shl
+        | BinOpSpec::Shr
+        | BinOpSpec::Sub => gen_impl_unsized(
+            props,
+            op_spec,
+            lhs_spec.with_wrapper(OperandTypeWrapperSpec::Ref),
+            rhs_spec.with_wrapper(OperandTypeWrapperSpec::Ref),
+            target,
+        ),
+        BinOpSpec::AddAssign
+        | BinOpSpec::BitAndAssign
+        | BinOpSpec::BitOrAssign
+        | BinOpSpec::BitXorAssign
+        | BinOpSpec::DivAssign
+        | BinOpSpec::MulAssign
+        | BinOpSpec::RemAssign
+        | BinOpSpec::ShlAssign
+        | BinOpSpec::ShrAssign
+        | BinOpSpec::SubAssign => Err(syn::Error::new(
+            props.derive_span(target),
+            format!(
+                "`#[opaque_typedef(derive({}))]` is not supported for unsized types",
+                target.as_ref()
+            ),
+        )),
+    }
+}
+
+/// Generates an impl for unsized types.
+///
+/// The left operand is converted into the owned inner value by `ToOwned`,
+/// and the operation is done as `Owned op &Inner`.
+/// If the function is specified by `#[opaque_typedef(ops(method = ..))]`, the
+/// result is computed by the function from `&Inner` operands instead.
+pub fn gen_impl_unsized(
+    props: &TypeProps,
+    op_spec: BinOpSpec,
+    lhs_spec: OperandSpec,
+    rhs_spec: OperandSpec,
+    target: Derive,
+) -> syn::Result<TokenStream> {
+    let alloc = paths::alloc(
+        props.derive_span(target),
+        format_args!(
+            "`#[opaque_typedef(derive({}))]` for unsized types",
+            target.as_ref()
+        ),
+    )?;
+    let ty_outer_generic = {
+        let ty_outer = &props.ty_outer;
+        let type_generics = &props.type_generics;
+        quote!(#ty_outer #type_generics)
+    };
+    let ty_inner = props.field_inner.ty();
+    let target_trait = op_spec.tokens_trait_path();
+    let ty_owned_inner = quote!(<#ty_inner as #alloc::borrow::ToOwned>::Owned);
+
+    let (generics, new_lts) = extend_generics(
+        Cow::Borrowed(props.generics),
+        lhs_spec.num_required_extra_lifetimes() + rhs_spec.num_required_extra_lifetimes(),
+        &[],
+    );
+    let (lts_lhs, lts_rhs) = new_lts.split_at(lhs_spec.num_required_extra_lifetimes());
+    let ty_lhs_impl = lhs_spec.tokens_ty_operand(lts_lhs, ty_inner, &ty_outer_generic);
+    let ty_rhs_impl = rhs_spec.tokens_ty_operand(lts_rhs, ty_inner, &ty_outer_generic);
+    let ty_rhs_inner = rhs_spec.tokens_ty_operand_inner(lts_rhs, ty_inner);
+    let method_name = op_spec.tokens_method();
+    let op_fn = props.ops_spec.unsized_fn(&method_name.to_string());
+    let ty_owned_result = match op_fn {
+        Some(_) => ty_owned_inner.clone(),
+        None => quote!(<#ty_owned_inner as #target_trait<#ty_rhs_inner>>::Output),
+    };
+    let (ty_output, from_owned_result) = tokens_unsized_output(props, &ty_owned_result);
+
+    let (generics, _) = {
+        let extra_preds = if props.has_type_params() {
+            let mut preds = vec![format!(
+                "{}: {}::borrow::ToOwned",
+                ty_inner.into_token_stream(),
+                alloc
+            )];
+            if op_fn.is_none() {
+                preds.push(match tokens_unsized_output_binding(props) {
+                    Some(binding) => format!(
+                        "{}: {}<{}, {}>",
+                        ty_owned_inner, target_trait, ty_rhs_inner, binding
+                    ),
+                    None => format!("{}: {}<{}>", ty_owned_inner, target_trait, ty_rhs_inner),
+                });
+            }
+            preds
+                .iter()
+                .map(|pred| {
+                    syn::parse_str::<syn::WherePredicate>(pred)
+                        .expect("Failed to generate `WherePredicate`")
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        extend_generics(generics, 0, &extra_preds)
+    };
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let other = quote!(other);
+    let self_inner = lhs_spec.tokens_inner(props, quote!(self));
+    let other_inner = rhs_spec.tokens_inner(props, &other);
+    let result = match op_fn {
+        Some(op_fn) => quote!(#op_fn(#self_inner, #other_inner)),
+        None => quote! {
+            #target_trait::#method_name(
+                <#ty_inner as #alloc::borrow::ToOwned>::to_owned(#self_inner),
+                #other_inner
+            )
+        },
+    };
+
+    Ok(quote! {
+        impl #impl_generics #target_trait<#ty_rhs_impl> for #ty_lhs_impl
+        #where_clause
+        {
+            type Output = #ty_output;
+
+            fn #method_name(self, #other: #ty_rhs_impl) -> Self::Output {
+                #from_owned_result(#result)
+            }
+        }
+    })
+}
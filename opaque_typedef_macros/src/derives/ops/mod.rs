@@ -5,7 +5,7 @@ use quote::ToTokens;
 use syn;
 
 use derives::Derive;
-use type_props::TypeProps;
+use type_props::{AssignOpsStrategy, OnInvalidOpResult, TypeProps, UnsizedOpOutput};
 
 pub mod binary;
 pub mod unary;
@@ -226,8 +226,26 @@ impl OpSpec {
         }
     }
 
-    pub fn gen_impl_sized(self, props: &TypeProps, _target: Derive) -> TokenStream {
-        match self {
+    pub fn gen_impl_sized(self, props: &TypeProps, target: Derive) -> syn::Result<TokenStream> {
+        if let (OpSpec::Binary { op_spec, .. }, &OnInvalidOpResult::Checked) =
+            (self, &props.ops_spec.on_invalid)
+        {
+            if op_spec.binop_for_assign().is_some()
+                && props.ops_spec.assign_ops == AssignOpsStrategy::ViaBinop
+            {
+                return Err(syn::Error::new(
+                    props.derive_span(target),
+                    format!(
+                        "`#[opaque_typedef(derive({}))]` cannot report the validation error, \
+                         so it cannot be used with \
+                         `#[opaque_typedef(ops(on_invalid = \"checked\"))]` \
+                         unless `#[opaque_typedef(assign_ops = \"in_place\")]` is specified",
+                        target.as_ref()
+                    ),
+                ));
+            }
+        }
+        let tokens = match self {
             OpSpec::Unary {
                 op_spec,
                 variation: OpImplVariation::Direct,
@@ -250,13 +268,87 @@ impl OpSpec {
                 lhs,
                 rhs,
             } => binary::gen_impl_sized_ref(props, op_spec, lhs, rhs),
+        };
+        Ok(tokens)
+    }
+
+    pub fn gen_impl_unsized(self, props: &TypeProps, target: Derive) -> syn::Result<TokenStream> {
+        match self {
+            OpSpec::Unary {
+                op_spec,
+                variation: OpImplVariation::References,
+                lhs,
+            } => unary::gen_impl_unsized_ref(props, op_spec, lhs, target),
+            OpSpec::Binary {
+                op_spec,
+                variation: OpImplVariation::References,
+                lhs,
+                rhs,
+            } => binary::gen_impl_unsized_ref(props, op_spec, lhs, rhs, target),
+            OpSpec::Unary {
+                variation: OpImplVariation::Direct,
+                ..
+            }
+            | OpSpec::Binary {
+                variation: OpImplVariation::Direct,
+                ..
+            } => Err(syn::Error::new(
+                props.derive_span(target),
+                format!(
+                    "`#[opaque_typedef(derive({}))]` is not supported for unsized types, \
+                     because unsized values cannot be passed by value \
+                     (use the variant for references instead)",
+                    target.as_ref()
+                ),
+            )),
+        }
+    }
+}
+
+/// Returns the expression to create the outer value from the result of the
+/// operation on the inner values, for sized types.
+///
+/// The expression has type `Result<Outer, Error>` if the type has
+/// `#[opaque_typedef(ops(on_invalid = "checked"))]`, and `Outer` otherwise.
+fn tokens_outer_from_result<T, U>(props: &TypeProps, ty_outer: T, result: U) -> TokenStream
+where
+    T: ToTokens,
+    U: ToTokens,
+{
+    let helper_trait = props.helper_trait();
+    match props.ops_spec.on_invalid {
+        OnInvalidOpResult::Panic => quote!(<#ty_outer as #helper_trait>::from_inner(#result)),
+        OnInvalidOpResult::Checked => {
+            quote!(<#ty_outer as #helper_trait>::try_from_inner(#result))
+        }
+        OnInvalidOpResult::Saturate(ref f) | OnInvalidOpResult::Wrap(ref f) => {
+            quote!(<#ty_outer as #helper_trait>::from_inner(#f(#result)))
         }
     }
+}
 
-    pub fn gen_impl_unsized(self, _props: &TypeProps, target: Derive) -> TokenStream {
-        panic!(
-            "`#[opaque_typedef(derive({:?}))]` is currently not supported for unsized types",
-            target
-        );
+/// Returns the output type and the function to convert the result of the
+/// operation on the owned inner value into the output, for unsized types.
+fn tokens_unsized_output<T: ToTokens>(
+    props: &TypeProps,
+    ty_owned_result: T,
+) -> (TokenStream, TokenStream) {
+    match (props.ops_spec.unsized_output, props.counterpart.as_ref()) {
+        (UnsizedOpOutput::Owned, Some(ty_owned)) => (
+            ty_owned.into_token_stream(),
+            quote!(<#ty_owned as ::opaque_typedef::OpaqueTypedef>::from_inner),
+        ),
+        _ => (ty_owned_result.into_token_stream(), quote!()),
+    }
+}
+
+/// Returns the constraint for the result of the operation on the owned inner
+/// value, for unsized types.
+fn tokens_unsized_output_binding(props: &TypeProps) -> Option<TokenStream> {
+    match (props.ops_spec.unsized_output, props.counterpart.as_ref()) {
+        (UnsizedOpOutput::Owned, Some(ty_owned)) => {
+            Some(quote!(Output = <#ty_owned as ::opaque_typedef::OpaqueTypedef>::Inner))
+        }
+        _ => None,
     }
 }
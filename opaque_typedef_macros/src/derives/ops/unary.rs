@@ -6,28 +6,41 @@ use proc_macro2::TokenStream;
 use quote::ToTokens;
 use syn;
 
-use type_props::TypeProps;
+use derives::Derive;
+use paths;
+use type_props::{OnInvalidOpResult, TypeProps};
 use utils::extend_generics;
 
-use super::{OperandSpec, OperandTypeSpec, OperandTypeWrapperSpec};
+use super::{
+    tokens_outer_from_result, tokens_unsized_output, tokens_unsized_output_binding, OperandSpec,
+    OperandTypeSpec, OperandTypeWrapperSpec,
+};
 
 /// Unary operations.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumString, EnumProperty)]
 pub enum UnaryOpSpec {
     /// `std::ops::Neg`.
     #[strum(props(
-        trait_ = "::std::ops::Neg",
+        trait_ = "ops::Neg",
         method = "neg",
         self_ = "self",
-        ty_ret = "Self::Output"
+        ty_ret = "Self::Output",
+        try_trait_ = "ops::TryNeg",
+        try_method = "try_neg",
+        saturating_trait_ = "ops::SaturatingNeg",
+        saturating_method = "saturating_neg",
+        wrapping_trait_ = "ops::WrappingNeg",
+        wrapping_method = "wrapping_neg"
     ))]
     Neg,
     /// `std::ops::Not`.
     #[strum(props(
-        trait_ = "::std::ops::Not",
+        trait_ = "ops::Not",
         method = "not",
         self_ = "self",
-        ty_ret = "Self::Output"
+        ty_ret = "Self::Output",
+        try_trait_ = "ops::TryNot",
+        try_method = "try_not"
     ))]
     Not,
 }
@@ -52,7 +65,9 @@ impl UnaryOpSpec {
 
     /// Returns target trait path.
     pub fn tokens_trait_path(self) -> TokenStream {
-        self.parse_prop::<syn::Path>("trait_").into_token_stream()
+        let core = paths::core();
+        let trait_ = self.parse_prop::<syn::Path>("trait_");
+        quote!(#core::#trait_)
     }
 
     /// Returns method name to implement.
@@ -83,14 +98,59 @@ impl UnaryOpSpec {
         self.parse_prop::<syn::Type>("ty_ret").into_token_stream()
     }
 
-    pub fn tokens_from_inner_result<T, U>(self, ty_outer: T, helper_trait: U) -> TokenStream
+    /// Returns the expression to be returned from the operator method.
+    pub fn tokens_from_inner_result<T, U>(
+        self,
+        props: &TypeProps,
+        ty_outer: T,
+        result: U,
+    ) -> TokenStream
     where
         T: ToTokens,
         U: ToTokens,
     {
         match self {
-            UnaryOpSpec::Neg | UnaryOpSpec::Not => quote!(<#ty_outer as #helper_trait>::from_inner),
+            UnaryOpSpec::Neg | UnaryOpSpec::Not => {
+                tokens_outer_from_result(props, ty_outer, result)
+            }
+        }
+    }
+
+    /// Returns the trait path and the method to operate on the inner value.
+    ///
+    /// For `#[opaque_typedef(ops(on_invalid = "saturate" | "wrap"))]`, the
+    /// saturating or wrapping operator is used if available.
+    pub fn tokens_inner_op(self, on_invalid: &OnInvalidOpResult) -> (TokenStream, TokenStream) {
+        use strum::EnumProperty;
+
+        let (trait_prop, method_prop) = match *on_invalid {
+            OnInvalidOpResult::Saturate(_) => ("saturating_trait_", "saturating_method"),
+            OnInvalidOpResult::Wrap(_) => ("wrapping_trait_", "wrapping_method"),
+            OnInvalidOpResult::Panic | OnInvalidOpResult::Checked => {
+                return (self.tokens_trait_path(), self.tokens_method());
+            }
+        };
+        if self.get_str(trait_prop).is_none() {
+            return (self.tokens_trait_path(), self.tokens_method());
         }
+        let trait_ = self.parse_prop::<syn::Path>(trait_prop);
+        let method = self.parse_prop::<syn::Ident>(method_prop);
+        (
+            quote!(::opaque_typedef::#trait_),
+            method.into_token_stream(),
+        )
+    }
+
+    /// Returns fallible trait path.
+    pub fn tokens_try_trait_path(self) -> TokenStream {
+        let trait_ = self.parse_prop::<syn::Path>("try_trait_");
+        quote!(::opaque_typedef::#trait_)
+    }
+
+    /// Returns fallible method name to implement.
+    pub fn tokens_try_method(self) -> TokenStream {
+        self.parse_prop::<syn::Ident>("try_method")
+            .into_token_stream()
     }
 
     pub fn tokens_lhs_inner_arg(self, props: &TypeProps, lhs_spec: OperandSpec) -> TokenStream {
@@ -161,7 +221,7 @@ pub fn gen_impl_sized(
     let (generics, ty_lhs_impl, ty_lhs_inner) = {
         let num_extra_lts_lhs = lhs_spec.num_required_extra_lifetimes();
         let num_extra_lts = num_extra_lts_lhs;
-        let generics = Cow::Borrowed(props.generics);
+        let generics = props.generics_with_helper_trait_preds();
         let (generics, new_lts) = if num_extra_lts > 0 {
             extend_generics(generics, num_extra_lts, &[])
         } else {
@@ -173,12 +233,15 @@ pub fn gen_impl_sized(
             lhs_spec.tokens_ty_operand_inner(&new_lts[..num_extra_lts_lhs], ty_inner);
         (generics, ty_lhs_impl, ty_lhs_inner)
     };
+    // Operator on the inner value.
+    let (inner_trait, inner_method) = op_spec.tokens_inner_op(&props.ops_spec.on_invalid);
+
     let (generics, _) = {
         let extra_preds = if props.has_type_params() {
             let pred = syn::parse_str::<syn::WherePredicate>(&format!(
                 "{}: {}<Output={}>",
                 ty_lhs_inner,
-                target_trait,
+                inner_trait,
                 ty_inner.into_token_stream()
             ))
             .expect("Failed to generate `WherePredicate`");
@@ -191,24 +254,153 @@ pub fn gen_impl_sized(
     let (impl_generics, _, where_clause) = generics.split_for_impl();
 
     let lhs_self_arg = op_spec.tokens_arg_self();
-    let associated = op_spec.tokens_associated_stuff(&ty_outer_generic);
     let method_name = op_spec.tokens_method();
-    let ty_ret = op_spec.tokens_ty_ret();
     let self_inner = op_spec.tokens_lhs_inner_arg(props, lhs_spec);
-    let from_inner_result =
-        op_spec.tokens_from_inner_result(&ty_outer_generic, props.helper_trait());
+    let (impl_trait, impl_method, associated, ty_ret) = match props.ops_spec.on_invalid {
+        OnInvalidOpResult::Checked => {
+            let helper_trait = props.helper_trait();
+            let core = paths::core();
+            (
+                op_spec.tokens_try_trait_path(),
+                op_spec.tokens_try_method(),
+                quote! {
+                    type Output = #ty_outer_generic;
+                    type Error = <#ty_outer_generic as #helper_trait>::Error;
+                },
+                quote!(#core::result::Result<Self::Output, Self::Error>),
+            )
+        }
+        _ => (
+            target_trait.clone(),
+            method_name.clone(),
+            op_spec.tokens_associated_stuff(&ty_outer_generic),
+            op_spec.tokens_ty_ret(),
+        ),
+    };
+    let body = op_spec.tokens_from_inner_result(
+        props,
+        &ty_outer_generic,
+        quote!(#inner_trait::#inner_method(#self_inner)),
+    );
 
     quote! {
-        impl #impl_generics #target_trait for #ty_lhs_impl
+        impl #impl_generics #impl_trait for #ty_lhs_impl
         #where_clause
         {
             #associated
 
-            fn #method_name(#lhs_self_arg) -> #ty_ret {
-                #from_inner_result(
-                    #target_trait::#method_name(#self_inner)
-                )
+            fn #impl_method(#lhs_self_arg) -> #ty_ret {
+                #body
             }
         }
     }
 }
+
+pub fn gen_impl_unsized_ref(
+    props: &TypeProps,
+    op_spec: UnaryOpSpec,
+    lhs_spec: OperandTypeSpec,
+    target: Derive,
+) -> syn::Result<TokenStream> {
+    assert!(lhs_spec != OperandTypeSpec::Inner);
+    match op_spec {
+        UnaryOpSpec::Neg | UnaryOpSpec::Not => gen_impl_unsized(
+            props,
+            op_spec,
+            lhs_spec.with_wrapper(OperandTypeWrapperSpec::Ref),
+            target,
+        ),
+    }
+}
+
+/// Generates an impl for unsized types.
+///
+/// The operand is converted into the owned inner value by `ToOwned`, and
+/// the operation is done for the owned value.
+/// If the function is specified by `#[opaque_typedef(ops(method = ..))]`, the
+/// result is computed by the function from the `&Inner` operand instead.
+pub fn gen_impl_unsized(
+    props: &TypeProps,
+    op_spec: UnaryOpSpec,
+    lhs_spec: OperandSpec,
+    target: Derive,
+) -> syn::Result<TokenStream> {
+    let alloc = paths::alloc(
+        props.derive_span(target),
+        format_args!(
+            "`#[opaque_typedef(derive({}))]` for unsized types",
+            target.as_ref()
+        ),
+    )?;
+    let ty_outer_generic = {
+        let ty_outer = &props.ty_outer;
+        let type_generics = &props.type_generics;
+        quote!(#ty_outer #type_generics)
+    };
+    let ty_inner = props.field_inner.ty();
+    let target_trait = op_spec.tokens_trait_path();
+    let ty_owned_inner = quote!(<#ty_inner as #alloc::borrow::ToOwned>::Owned);
+
+    let (generics, new_lts) = extend_generics(
+        Cow::Borrowed(props.generics),
+        lhs_spec.num_required_extra_lifetimes(),
+        &[],
+    );
+    let ty_lhs_impl = lhs_spec.tokens_ty_operand(&new_lts, ty_inner, &ty_outer_generic);
+    let method_name = op_spec.tokens_method();
+    let op_fn = props.ops_spec.unsized_fn(&method_name.to_string());
+    let ty_owned_result = match op_fn {
+        Some(_) => ty_owned_inner.clone(),
+        None => quote!(<#ty_owned_inner as #target_trait>::Output),
+    };
+    let (ty_output, from_owned_result) = tokens_unsized_output(props, &ty_owned_result);
+
+    let (generics, _) = {
+        let extra_preds = if props.has_type_params() {
+            let mut preds = vec![format!(
+                "{}: {}::borrow::ToOwned",
+                ty_inner.into_token_stream(),
+                alloc
+            )];
+            if op_fn.is_none() {
+                preds.push(match tokens_unsized_output_binding(props) {
+                    Some(binding) => format!("{}: {}<{}>", ty_owned_inner, target_trait, binding),
+                    None => format!("{}: {}", ty_owned_inner, target_trait),
+                });
+            }
+            preds
+                .iter()
+                .map(|pred| {
+                    syn::parse_str::<syn::WherePredicate>(pred)
+                        .expect("Failed to generate `WherePredicate`")
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        extend_generics(generics, 0, &extra_preds)
+    };
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let self_inner = lhs_spec.tokens_inner(props, quote!(self));
+    let result = match op_fn {
+        Some(op_fn) => quote!(#op_fn(#self_inner)),
+        None => quote! {
+            #target_trait::#method_name(
+                <#ty_inner as #alloc::borrow::ToOwned>::to_owned(#self_inner)
+            )
+        },
+    };
+
+    Ok(quote! {
+        impl #impl_generics #target_trait for #ty_lhs_impl
+        #where_clause
+        {
+            type Output = #ty_output;
+
+            fn #method_name(self) -> Self::Output {
+                #from_owned_result(#result)
+            }
+        }
+    })
+}
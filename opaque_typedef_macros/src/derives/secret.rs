@@ -0,0 +1,135 @@
+//! Impl generators for secret-bearing types (`#[opaque_typedef(secret)]`).
+
+use proc_macro2::TokenStream;
+use syn;
+
+use paths;
+use type_props::{Sizedness, TypeProps};
+
+use super::Derive;
+
+/// Derive targets which reveal the inner value.
+const REVEALING_DERIVES: &[Derive] = &[
+    Derive::AsMutDeref,
+    Derive::AsMutInner,
+    Derive::AsRefDeref,
+    Derive::AsRefInner,
+    Derive::Deref,
+    Derive::DerefMut,
+    Derive::IntoInner,
+    Derive::Binary,
+    Derive::BinaryDeref,
+    Derive::DebugDeref,
+    Derive::DebugNamed,
+    Derive::DebugTransparent,
+    Derive::Display,
+    Derive::DisplayDeref,
+    Derive::LowerExp,
+    Derive::LowerExpDeref,
+    Derive::LowerHex,
+    Derive::LowerHexDeref,
+    Derive::Octal,
+    Derive::OctalDeref,
+    Derive::Pointer,
+    Derive::PointerDeref,
+    Derive::UpperExp,
+    Derive::UpperExpDeref,
+    Derive::UpperHex,
+    Derive::UpperHexDeref,
+];
+
+/// Returns an error if the target reveals the inner value of the secret type
+/// and it is not explicitly forced.
+pub fn check_derive(target: Derive, props: &TypeProps) -> syn::Result<()> {
+    let spec = match props.secret_spec {
+        Some(ref spec) => spec,
+        None => return Ok(()),
+    };
+    if REVEALING_DERIVES.contains(&target) && !spec.allowed_derives.contains(&target) {
+        return Err(syn::Error::new(
+            props.derive_span(target),
+            format!(
+                "`#[opaque_typedef(derive({target}))]` reveals the inner value of \
+                 `#[opaque_typedef(secret)]` type; \
+                 specify `#[opaque_typedef(secret(allow_derive({target})))]` to force it",
+                target = target.as_ref()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Generates impls for secret-bearing types.
+///
+/// `Debug` and `Display` print `Outer(<redacted>)`, and `Drop` overwrites
+/// the inner value for sized types.
+pub fn gen_impls_secret(props: &TypeProps) -> syn::Result<TokenStream> {
+    if props.secret_spec.is_none() {
+        return Ok(quote!());
+    }
+    let core = paths::core();
+    let ty_outer = props.ty_outer;
+    let impl_generics = &props.impl_generics;
+    let type_generics = &props.type_generics;
+    let where_clause = &props.where_clause;
+    let redacted = format!("{}(<redacted>)", ty_outer);
+
+    // Forced `Debug*` and `Display` derive targets are used instead.
+    let is_debug_forced = [
+        Derive::DebugDeref,
+        Derive::DebugNamed,
+        Derive::DebugTransparent,
+    ]
+    .iter()
+    .any(|derive| props.derives.contains(derive));
+    let impl_debug = if is_debug_forced {
+        quote!()
+    } else {
+        quote! {
+            impl #impl_generics #core::fmt::Debug for #ty_outer #type_generics
+            #where_clause
+            {
+                fn fmt(&self, f: &mut #core::fmt::Formatter) -> #core::fmt::Result {
+                    f.write_str(#redacted)
+                }
+            }
+        }
+    };
+    let impl_display = if props.derives.contains(&Derive::Display)
+        || props.derives.contains(&Derive::DisplayDeref)
+    {
+        quote!()
+    } else {
+        quote! {
+            impl #impl_generics #core::fmt::Display for #ty_outer #type_generics
+            #where_clause
+            {
+                fn fmt(&self, f: &mut #core::fmt::Formatter) -> #core::fmt::Result {
+                    f.write_str(#redacted)
+                }
+            }
+        }
+    };
+    // References to unsized types don't own the inner value.
+    let impl_drop = match props.inner_sizedness {
+        Sizedness::Sized => {
+            let name_inner = props.field_inner.name();
+            quote! {
+                impl #impl_generics #core::ops::Drop for #ty_outer #type_generics
+                #where_clause
+                {
+                    fn drop(&mut self) {
+                        ::opaque_typedef::secret::Zeroize::zeroize(&mut self.#name_inner);
+                    }
+                }
+            }
+        }
+        Sizedness::Unsized => quote!(),
+    };
+
+    Ok(quote! {
+        #impl_debug
+        #impl_display
+        #impl_drop
+    })
+}
@@ -0,0 +1,156 @@
+//! Impl generators for `serde::{Serialize, Deserialize}` traits.
+
+use std::borrow::Cow;
+
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn;
+
+use paths;
+use type_props::{Sizedness, TypeProps};
+use utils::extend_generics;
+
+/// Generates an impl for the target.
+pub fn gen_impl_serialize(props: &TypeProps) -> TokenStream {
+    let core = paths::core();
+    let ty_outer = props.ty_outer;
+    let type_generics = &props.type_generics;
+    let ty_inner = props.field_inner.ty();
+    let self_as_inner = props.tokens_outer_expr_as_inner(quote!(self));
+    let extra_preds = if props.has_type_params() {
+        let pred = syn::parse_str::<syn::WherePredicate>(&format!(
+            "{}: ::opaque_typedef::serde::Serialize",
+            ty_inner.into_token_stream()
+        ))
+        .expect("Failed to generate `WherePredicate`");
+        vec![pred]
+    } else {
+        Vec::new()
+    };
+    let (generics, _) = extend_generics(Cow::Borrowed(props.generics), 0, &extra_preds);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::opaque_typedef::serde::Serialize for #ty_outer #type_generics
+        #where_clause
+        {
+            fn serialize<__S>(
+                &self,
+                serializer: __S
+            ) -> #core::result::Result<__S::Ok, __S::Error>
+            where
+                __S: ::opaque_typedef::serde::Serializer,
+            {
+                <#ty_inner as ::opaque_typedef::serde::Serialize>::serialize(
+                    #self_as_inner,
+                    serializer
+                )
+            }
+        }
+    }
+}
+
+/// Generates an impl for the target.
+///
+/// For unsized types, `Deserialize<'de> for &'a Outer` is implemented and
+/// the value is borrowed from the deserializer (zero-copy).
+pub fn gen_impl_deserialize(props: &TypeProps) -> TokenStream {
+    let core = paths::core();
+    let ty_outer = props.ty_outer;
+    let type_generics = &props.type_generics;
+    let ty_inner = props.field_inner.ty().into_token_stream();
+    let helper_trait = props.helper_trait();
+    let map_err = tokens_map_err(props);
+    match props.inner_sizedness {
+        Sizedness::Sized => {
+            let (generics, new_lifetimes) =
+                extend_generics(props.generics_with_helper_trait_preds(), 1, &[]);
+            let lt_de = &new_lifetimes[0];
+            let extra_preds = if props.has_type_params() {
+                let pred = syn::parse_str::<syn::WherePredicate>(&format!(
+                    "{}: ::opaque_typedef::serde::Deserialize<{}>",
+                    ty_inner,
+                    lt_de.into_token_stream()
+                ))
+                .expect("Failed to generate `WherePredicate`");
+                vec![pred]
+            } else {
+                Vec::new()
+            };
+            let (generics, _) = extend_generics(generics, 0, &extra_preds);
+            let (impl_generics, _, where_clause) = generics.split_for_impl();
+            quote! {
+                impl #impl_generics
+                    ::opaque_typedef::serde::Deserialize<#lt_de> for #ty_outer #type_generics
+                #where_clause
+                {
+                    fn deserialize<__D>(
+                        deserializer: __D
+                    ) -> #core::result::Result<Self, __D::Error>
+                    where
+                        __D: ::opaque_typedef::serde::Deserializer<#lt_de>,
+                    {
+                        let inner = <#ty_inner as ::opaque_typedef::serde::Deserialize<#lt_de>>
+                            ::deserialize(deserializer)?;
+                        <#ty_outer #type_generics as #helper_trait>::try_from_inner(inner)
+                            .map_err(#map_err)
+                    }
+                }
+            }
+        }
+        Sizedness::Unsized => {
+            let (generics, new_lifetimes) =
+                extend_generics(props.generics_with_helper_trait_preds(), 2, &[]);
+            let lt_de = &new_lifetimes[0];
+            let lt_ref = &new_lifetimes[1];
+            let mut extra_preds = vec![syn::parse_str::<syn::WherePredicate>(&format!(
+                "{}: {}",
+                lt_de.into_token_stream(),
+                lt_ref.into_token_stream()
+            ))
+            .expect("Failed to generate `WherePredicate`")];
+            if props.has_type_params() {
+                let pred = syn::parse_str::<syn::WherePredicate>(&format!(
+                    "&{} {}: ::opaque_typedef::serde::Deserialize<{}>",
+                    lt_ref.into_token_stream(),
+                    ty_inner,
+                    lt_de.into_token_stream()
+                ))
+                .expect("Failed to generate `WherePredicate`");
+                extra_preds.push(pred);
+            }
+            let (generics, _) = extend_generics(generics, 0, &extra_preds);
+            let (impl_generics, _, where_clause) = generics.split_for_impl();
+            quote! {
+                impl #impl_generics
+                    ::opaque_typedef::serde::Deserialize<#lt_de>
+                    for &#lt_ref #ty_outer #type_generics
+                #where_clause
+                {
+                    fn deserialize<__D>(
+                        deserializer: __D
+                    ) -> #core::result::Result<Self, __D::Error>
+                    where
+                        __D: ::opaque_typedef::serde::Deserializer<#lt_de>,
+                    {
+                        let inner = <&#lt_ref #ty_inner as ::opaque_typedef::serde::Deserialize<#lt_de>>
+                            ::deserialize(deserializer)?;
+                        <#ty_outer #type_generics as #helper_trait>::try_from_inner(inner)
+                            .map_err(#map_err)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns a function to convert a validation error into a deserialization
+/// error.
+///
+/// The message is similar to the panic message of `Outer::from_inner`.
+fn tokens_map_err(props: &TypeProps) -> TokenStream {
+    let custom = quote!(<__D::Error as ::opaque_typedef::serde::de::Error>::custom);
+    match props.validation_spec.error_msg {
+        Some(ref msg) => quote!(|e| #custom(format_args!("{}: {:?}", #msg, e))),
+        None => quote!(|e| #custom(format_args!("{:?}", e))),
+    }
+}
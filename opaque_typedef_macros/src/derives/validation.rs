@@ -0,0 +1,352 @@
+//! Impl generators for validation.
+
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn;
+use syn::spanned::Spanned;
+
+use paths;
+use type_props::{Sizedness, TypeProps};
+use utils::extend_generics;
+
+/// Generates the validation error type and the validator for
+/// `#[opaque_typedef(validation(error = "auto"))]`.
+pub fn gen_impls_auto_error(props: &TypeProps) -> TokenStream {
+    let spec = match props.validation_spec.auto_error {
+        Some(ref spec) => spec,
+        None => return quote!(),
+    };
+    let core = paths::core();
+    let vis = props.vis;
+    let ty_outer = props.ty_outer;
+    let ty_inner = props.field_inner.ty();
+    let ty_error = &spec.ty_error;
+    let fn_predicate = &spec.fn_predicate;
+    let message = &spec.message;
+    let doc_error = format!("Validation error of `{}`.", ty_outer);
+
+    let (def_error, new_error, new_error_cloned, methods_value) = if spec.keep_value {
+        let def_error = quote! {
+            #vis struct #ty_error {
+                value: #ty_inner,
+            }
+        };
+        let methods_value = quote! {
+            /// Returns the reference to the rejected value.
+            #vis fn value(&self) -> &#ty_inner {
+                &self.value
+            }
+
+            /// Takes and returns the rejected value.
+            #vis fn into_value(self) -> #ty_inner {
+                self.value
+            }
+        };
+        (
+            def_error,
+            quote!(#ty_error { value: __inner }),
+            quote!(#ty_error { value: #core::clone::Clone::clone(__inner) }),
+            methods_value,
+        )
+    } else {
+        let def_error = quote! {
+            #vis struct #ty_error {
+                _priv: (),
+            }
+        };
+        let new_error = quote!(#ty_error { _priv: () });
+        (def_error, new_error.clone(), new_error, quote!())
+    };
+    let impl_std_error = match paths::std_opt() {
+        Some(std) => quote!(impl #std::error::Error for #ty_error {}),
+        None => quote!(),
+    };
+    let fn_validate = match props.inner_sizedness {
+        // The rejected value is handed back with the error.
+        Sizedness::Sized if props.validation_spec.rejected => quote! {
+            fn __opaque_typedef_validate(
+                __inner: #ty_inner
+            ) -> #core::result::Result<#ty_inner, ::opaque_typedef::Rejected<#ty_inner, #ty_error>> {
+                if #fn_predicate(&__inner) {
+                    Ok(__inner)
+                } else {
+                    Err(::opaque_typedef::Rejected::new(__inner, #new_error))
+                }
+            }
+        },
+        Sizedness::Sized => quote! {
+            fn __opaque_typedef_validate(
+                __inner: #ty_inner
+            ) -> #core::result::Result<#ty_inner, #ty_error> {
+                if #fn_predicate(&__inner) {
+                    Ok(__inner)
+                } else {
+                    Err(#new_error)
+                }
+            }
+        },
+        // Both `&Inner` and `&mut Inner` are validated.
+        Sizedness::Unsized => quote! {
+            fn __opaque_typedef_validate<__T: #core::borrow::Borrow<#ty_inner>>(
+                __inner: __T
+            ) -> #core::result::Result<__T, #ty_error> {
+                if #fn_predicate(#core::borrow::Borrow::<#ty_inner>::borrow(&__inner)) {
+                    Ok(__inner)
+                } else {
+                    Err(#new_error)
+                }
+            }
+        },
+    };
+
+    // Checks the value without taking the ownership.
+    let fn_check = match props.inner_sizedness {
+        Sizedness::Sized => quote! {
+            #[doc(hidden)]
+            fn __opaque_typedef_check(
+                __inner: &#ty_inner
+            ) -> #core::result::Result<(), #ty_error> {
+                if #fn_predicate(__inner) {
+                    Ok(())
+                } else {
+                    Err(#new_error_cloned)
+                }
+            }
+        },
+        Sizedness::Unsized => quote!(),
+    };
+
+    quote! {
+        #[doc = #doc_error]
+        #[derive(Debug, Clone, PartialEq)]
+        #def_error
+
+        impl #ty_error {
+            /// Returns the error message.
+            #vis fn message(&self) -> &'static str {
+                #message
+            }
+
+            #methods_value
+        }
+
+        impl #core::fmt::Display for #ty_error {
+            fn fmt(&self, f: &mut #core::fmt::Formatter) -> #core::fmt::Result {
+                f.write_str(#message)
+            }
+        }
+
+        #impl_std_error
+
+        impl #ty_outer {
+            #[doc(hidden)]
+            #fn_validate
+
+            #fn_check
+        }
+    }
+}
+
+/// Generates the validator from the checker given by
+/// `#[opaque_typedef(validation(check = ..))]`.
+///
+/// For `#[opaque_typedef(validation(with = ..))]`, the checker is also
+/// generated.
+pub fn gen_impl_check_validator(props: &TypeProps) -> TokenStream {
+    let fn_check = match props.validation_spec.fn_check {
+        Some(ref fn_check) if props.validation_spec.auto_error.is_none() => fn_check,
+        _ => return quote!(),
+    };
+    let core = paths::core();
+    let ty_outer = props.ty_outer;
+    let ty_inner = props.field_inner.ty();
+    let ty_error = props.validation_spec.tokens_ty_error();
+    let impl_generics = &props.impl_generics;
+    let type_generics = &props.type_generics;
+    let where_clause = &props.where_clause;
+    let fn_check_with = match props.validation_spec.validator_with {
+        Some(ref validator) => quote! {
+            #[doc(hidden)]
+            fn __opaque_typedef_check(
+                __inner: &#ty_inner
+            ) -> #core::result::Result<(), #ty_error> {
+                ::opaque_typedef::validator::Validator::<#ty_inner>::validate(&(#validator), __inner)
+            }
+        },
+        None => quote!(),
+    };
+    let fn_validate = match props.inner_sizedness {
+        // The rejected value is handed back with the error.
+        Sizedness::Sized => quote! {
+            fn __opaque_typedef_validate(
+                __inner: #ty_inner
+            ) -> #core::result::Result<#ty_inner, ::opaque_typedef::Rejected<#ty_inner, #ty_error>> {
+                match #fn_check(&__inner) {
+                    Ok(()) => Ok(__inner),
+                    Err(__e) => Err(::opaque_typedef::Rejected::new(__inner, __e)),
+                }
+            }
+        },
+        // Both `&Inner` and `&mut Inner` are validated.
+        Sizedness::Unsized => quote! {
+            fn __opaque_typedef_validate<__T: #core::borrow::Borrow<#ty_inner>>(
+                __inner: __T
+            ) -> #core::result::Result<__T, #ty_error> {
+                #fn_check(#core::borrow::Borrow::<#ty_inner>::borrow(&__inner))?;
+                Ok(__inner)
+            }
+        },
+    };
+
+    quote! {
+        impl #impl_generics #ty_outer #type_generics
+        #where_clause
+        {
+            #[doc(hidden)]
+            #fn_validate
+
+            #fn_check_with
+        }
+    }
+}
+
+/// Generates the checker which rejects values which are not normalized, for
+/// unsized types with `#[opaque_typedef(validation(normalize = ..))]`.
+///
+/// A value is normalized if the normalizer returns it as borrowed.
+pub fn gen_impl_check_normalized(props: &TypeProps) -> TokenStream {
+    let fn_normalize = match (props.inner_sizedness, &props.validation_spec.fn_normalize) {
+        (Sizedness::Unsized, Some(fn_normalize)) => fn_normalize,
+        _ => return quote!(),
+    };
+    let new_error = match (
+        &props.validation_spec.fn_not_normalized,
+        &props.validation_spec.auto_error,
+    ) {
+        (Some(fn_not_normalized), _) => quote!(#fn_not_normalized(__inner)),
+        (None, Some(auto)) => {
+            let ty_error = &auto.ty_error;
+            quote!(#ty_error { _priv: () })
+        }
+        // The error is reported by the builder.
+        (None, None) => return quote!(),
+    };
+    let alloc = match paths::alloc(
+        fn_normalize.span(),
+        format_args!("`#[opaque_typedef(validation(normalize = ..))]` for unsized types"),
+    ) {
+        Ok(alloc) => alloc,
+        // The error is reported by `gen_impl_from_inner_cow`.
+        Err(_) => return quote!(),
+    };
+    let core = paths::core();
+    let ty_outer = props.ty_outer;
+    let ty_inner = props.field_inner.ty();
+    let ty_error = props.validation_spec.tokens_ty_error();
+    let impl_generics = &props.impl_generics;
+    let type_generics = &props.type_generics;
+    let where_clause = &props.where_clause;
+
+    quote! {
+        impl #impl_generics #ty_outer #type_generics
+        #where_clause
+        {
+            #[doc(hidden)]
+            fn __opaque_typedef_check_normalized(
+                __inner: &#ty_inner
+            ) -> #core::result::Result<(), #ty_error> {
+                match #fn_normalize(__inner) {
+                    #alloc::borrow::Cow::Borrowed(_) => Ok(()),
+                    #alloc::borrow::Cow::Owned(_) => Err(#new_error),
+                }
+            }
+        }
+    }
+}
+
+/// Generates `Outer::from_inner_cow` for unsized types with
+/// `#[opaque_typedef(validation(normalize = ..))]`.
+///
+/// The normalized value is converted into the owned counterpart without
+/// cloning again.
+pub fn gen_impl_from_inner_cow(props: &TypeProps) -> syn::Result<TokenStream> {
+    let fn_normalize = match (props.inner_sizedness, &props.validation_spec.fn_normalize) {
+        (Sizedness::Unsized, Some(fn_normalize)) => fn_normalize,
+        _ => return Ok(quote!()),
+    };
+    let ty_owned = match props.counterpart {
+        Some(ref ty) => ty,
+        None => {
+            return Err(syn::Error::new(
+                fn_normalize.span(),
+                "`#[opaque_typedef(validation(normalize = ..))]` for unsized types requires \
+                 `#[opaque_typedef(owned = ..)]`",
+            ));
+        }
+    };
+    let core = paths::core();
+    let alloc = paths::alloc(
+        fn_normalize.span(),
+        format_args!("`#[opaque_typedef(validation(normalize = ..))]` for unsized types"),
+    )?;
+    let vis = props.vis;
+    let ty_outer = props.ty_outer;
+    let ty_inner = props.field_inner.ty();
+    let ty_error = props.validation_spec.tokens_ty_error();
+    let type_generics = &props.type_generics;
+    let helper_owned = quote!(::opaque_typedef::OpaqueTypedef);
+    let helper_borrowed = quote!(::opaque_typedef::OpaqueTypedefUnsized);
+    // The value is already normalized, so only the validator is applied.
+    let inner_try_validated = props.validation_spec.tokens_try_validated(quote!(__inner));
+    let extra_preds = if props.has_type_params() {
+        let pred = syn::parse_str::<syn::WherePredicate>(&format!(
+            "{}: {}::borrow::ToOwned<Owned = <{} as {}>::Inner>",
+            ty_inner.into_token_stream(),
+            alloc,
+            ty_owned.into_token_stream(),
+            helper_owned,
+        ))
+        .expect("Failed to generate `WherePredicate`");
+        vec![pred]
+    } else {
+        Vec::new()
+    };
+    let (generics, _) = extend_generics(props.generics_with_helper_trait_preds(), 0, &extra_preds);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let doc = format!(
+        "Creates a normalized `{}` from the inner value.\n\n\
+         The value is borrowed if it is already normalized.",
+        ty_outer
+    );
+
+    Ok(quote! {
+        impl #impl_generics #ty_outer #type_generics
+        #where_clause
+        {
+            #[doc = #doc]
+            #vis fn from_inner_cow(
+                __inner: &#ty_inner
+            ) -> #core::result::Result<#alloc::borrow::Cow<'_, Self>, #ty_error> {
+                match #fn_normalize(__inner) {
+                    #alloc::borrow::Cow::Borrowed(__inner) => {
+                        let __inner = #inner_try_validated;
+                        let __outer = unsafe {
+                            <Self as #helper_borrowed>::from_inner_unchecked(__inner)
+                        };
+                        Ok(#alloc::borrow::Cow::Borrowed(__outer))
+                    }
+                    #alloc::borrow::Cow::Owned(__inner) => {
+                        {
+                            let __inner = #core::borrow::Borrow::<#ty_inner>::borrow(&__inner);
+                            let _ = #inner_try_validated;
+                        }
+                        let __owned = unsafe {
+                            <#ty_owned as #helper_owned>::from_inner_unchecked(__inner)
+                        };
+                        Ok(#alloc::borrow::Cow::Owned(__owned))
+                    }
+                }
+            }
+        }
+    })
+}
@@ -19,6 +19,7 @@ use type_props::{Sizedness, TypeProps};
 
 mod attrs;
 mod derives;
+mod paths;
 mod type_props;
 mod utils;
 
@@ -39,7 +40,16 @@ pub fn opaque_typedef_unsized(input: proc_macro::TokenStream) -> proc_macro::Tok
 }
 
 /// Generates additional impls for a `#[derive(OpaqueTypedef*)]`-ed type.
+///
+/// If there are errors, `compile_error!` for each error is generated instead.
 fn gen_opaque_typedef_impls(input: &DeriveInput, sizedness: Sizedness) -> TokenStream {
-    let props = TypeProps::load(&input, sizedness);
-    props.gen_impls()
+    let mut errors = Vec::new();
+    let impls =
+        TypeProps::load(&input, sizedness, &mut errors).map(|props| props.gen_impls(&mut errors));
+    if errors.is_empty() {
+        impls.expect("Should never happen: `TypeProps` should be loaded if there are no errors")
+    } else {
+        let compile_errors = errors.iter().map(syn::Error::to_compile_error);
+        quote!(#(#compile_errors)*)
+    }
 }
@@ -0,0 +1,69 @@
+//! Paths to the standard library crates used in generated codes.
+//!
+//! If `std` feature is enabled, generated codes use `::std` paths.
+//! Otherwise, they use `::core` and `::alloc` paths, so that they can be used
+//! in `#![no_std]` crates.
+
+use std::fmt;
+
+use proc_macro2::{Span, TokenStream};
+use syn;
+
+/// Returns the path to the crate which provides `core` items.
+pub fn core() -> TokenStream {
+    if cfg!(feature = "std") {
+        quote!(::std)
+    } else {
+        quote!(::core)
+    }
+}
+
+/// Returns the path to the crate which provides `alloc` items.
+///
+/// Returns an error at the given span if neither `std` nor `alloc` feature is
+/// enabled.
+pub fn alloc(span: Span, user: fmt::Arguments) -> syn::Result<TokenStream> {
+    if cfg!(feature = "std") {
+        Ok(quote!(::std))
+    } else if cfg!(feature = "alloc") {
+        Ok(quote!(::alloc))
+    } else {
+        Err(syn::Error::new(
+            span,
+            format!(
+                "{} requires `alloc` or `std` feature of `opaque_typedef_macros`, \
+                 but both are disabled",
+                user
+            ),
+        ))
+    }
+}
+
+/// Returns the path to `std` crate.
+///
+/// Returns an error at the given span if `std` feature is not enabled.
+pub fn std(span: Span, user: fmt::Arguments) -> syn::Result<TokenStream> {
+    if cfg!(feature = "std") {
+        Ok(quote!(::std))
+    } else {
+        Err(syn::Error::new(
+            span,
+            format!(
+                "{} requires `std` feature of `opaque_typedef_macros`, but it is disabled",
+                user
+            ),
+        ))
+    }
+}
+
+/// Returns the path to `std` crate if `std` feature is enabled.
+///
+/// This is for optional impls (such as `std::error::Error`), which are not
+/// generated without `std` feature.
+pub fn std_opt() -> Option<TokenStream> {
+    if cfg!(feature = "std") {
+        Some(quote!(::std))
+    } else {
+        None
+    }
+}
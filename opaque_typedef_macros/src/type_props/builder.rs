@@ -1,60 +1,268 @@
 //! Utilities to build `type_props::TypeProps`.
 
+use std::collections::HashMap;
+
+use proc_macro2::Span;
 use quote::ToTokens;
 use syn;
 use syn::DeriveInput;
 
 use attrs::{get_meta_content_by_path, has_word_meta, is_attr_with_path};
 use derives::Derive;
-use type_props::{CmpSpec, DerefSpec, Field, Sizedness, TypeProps, ValidationSpec};
-use utils::expect_singleton_iter;
+use paths;
+use type_props::{
+    AssignOpsStrategy, AutoErrorSpec, CmpSpec, DerefSpec, DisplaySpec, ExtraField, Field,
+    OnInvalidOpResult, OpsSpec, SecretSpec, Sizedness, TypeProps, UnsizedOpOutput, ValidationSpec,
+};
+use utils::{expect_singleton_iter, SingletonIterResult};
 
 /// Returns `#[repr(..)]` metadata.
-fn get_repr_meta(attrs: &[syn::Attribute]) -> Option<syn::Meta> {
+fn get_repr_meta(attrs: &[syn::Attribute], errors: &mut Vec<syn::Error>) -> Option<syn::Meta> {
     let iter = attrs
         .iter()
         .filter(|attr| is_attr_with_path(attr, &["repr"]))
         .filter_map(|attr| attr.interpret_meta());
-    expect_singleton_iter(iter)
-        .at_most_one()
-        .expect("Multiple `#[repr(..)]` are not supported")
+    match expect_singleton_iter(iter) {
+        SingletonIterResult::None => None,
+        SingletonIterResult::Single(meta) => Some(meta),
+        SingletonIterResult::Multiple(first, second) => {
+            errors.push(syn::Error::new_spanned(
+                second,
+                "Multiple `#[repr(..)]` are not supported",
+            ));
+            Some(first)
+        }
+    }
 }
 
-/// Returns a field marked (explicitly or implicitly) as "inner".
-fn get_inner_field(data: &syn::Data) -> Field {
+/// Returns a field marked (explicitly or implicitly) as "inner", and other
+/// fields.
+fn get_fields<'a>(
+    input: &'a DeriveInput,
+    sizedness: Sizedness,
+    errors: &mut Vec<syn::Error>,
+) -> Option<(Field<'a>, Vec<ExtraField<'a>>)> {
     // Currently, only struct is supported.
-    let fields = match *data {
+    let fields = match input.data {
         syn::Data::Struct(ref data) => &data.fields,
-        syn::Data::Enum(..) => panic!("Enum types are not supported"),
-        syn::Data::Union(..) => panic!("Currently, union types are not supported"),
+        syn::Data::Enum(ref data) => {
+            errors.push(syn::Error::new(
+                data.enum_token.span,
+                "Enum types are not supported",
+            ));
+            return None;
+        }
+        syn::Data::Union(ref data) => {
+            errors.push(syn::Error::new(
+                data.union_token.span,
+                "Currently, union types are not supported",
+            ));
+            return None;
+        }
     };
     // Get fields with `syn::Fields` type.
     let fields = match *fields {
         syn::Fields::Named(ref fields) => &fields.named,
         syn::Fields::Unnamed(ref fields) => &fields.unnamed,
-        syn::Fields::Unit => panic!("Types with no fields are not supported"),
+        syn::Fields::Unit => {
+            errors.push(syn::Error::new_spanned(
+                &input.ident,
+                "Types with no fields are not supported",
+            ));
+            return None;
+        }
     };
+    if fields.is_empty() {
+        errors.push(syn::Error::new_spanned(
+            &input.ident,
+            "Types with no fields are not supported",
+        ));
+        return None;
+    }
+    let fields = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            if field.ident.is_some() {
+                Field::Named(field)
+            } else {
+                Field::Unnamed(field, index)
+            }
+        })
+        .collect::<Vec<_>>();
+
     // If there is only one field, it is the inner field.
-    if fields.len() == 1 {
-        let field = &fields[0];
-        if field.ident.is_some() {
-            return Field::Named(field);
-        } else {
-            return Field::Unnamed(field, 0);
+    // Otherwise, the inner field should be marked by `#[opaque_typedef(inner)]`.
+    let inner_index = if fields.len() == 1 {
+        0
+    } else {
+        let marked = fields
+            .iter()
+            .enumerate()
+            .filter(|&(_, field)| is_marked_as_inner(field.raw()))
+            .map(|(index, _)| index);
+        match expect_singleton_iter(marked) {
+            SingletonIterResult::None => {
+                errors.push(syn::Error::new_spanned(
+                    &input.ident,
+                    "Outer type with multiple fields should have a field \
+                     marked as `#[opaque_typedef(inner)]`",
+                ));
+                return None;
+            }
+            SingletonIterResult::Single(index) => index,
+            SingletonIterResult::Multiple(_, second) => {
+                errors.push(syn::Error::new_spanned(
+                    fields[second].raw(),
+                    "`#[opaque_typedef(inner)]` can be specified at most once for each type",
+                ));
+                return None;
+            }
+        }
+    };
+
+    let mut extra_fields = Vec::with_capacity(fields.len() - 1);
+    let mut field_inner = None;
+    for (index, field) in fields.into_iter().enumerate() {
+        let default = get_field_default(field.raw(), errors);
+        if index == inner_index {
+            if let Ok(Some(default)) = default {
+                errors.push(syn::Error::new_spanned(
+                    default,
+                    "`#[opaque_typedef(default = ..)]` cannot be specified for the inner field",
+                ));
+            }
+            field_inner = Some(field);
+            continue;
         }
+        let default = match default {
+            Ok(v) => v,
+            // The error is already recorded.
+            Err(()) => continue,
+        };
+        let is_phantom = default.is_none() && is_phantom_data(field.ty());
+        let init = match (default, sizedness) {
+            (Some(default), Sizedness::Sized) => default.into_token_stream(),
+            (Some(default), Sizedness::Unsized) => {
+                errors.push(syn::Error::new_spanned(
+                    default,
+                    "`#[opaque_typedef(default = ..)]` is not supported for unsized types, \
+                     because the outer value is not constructed but casted from the inner value",
+                ));
+                continue;
+            }
+            (None, _) if is_phantom => {
+                let core = paths::core();
+                quote!(#core::marker::PhantomData)
+            }
+            (None, _) => {
+                errors.push(syn::Error::new_spanned(
+                    field.raw(),
+                    "Fields other than the inner field should be `PhantomData` \
+                     (`core::marker::PhantomData` or `std::marker::PhantomData`) or \
+                     have `#[opaque_typedef(default = ..)]`",
+                ));
+                continue;
+            }
+        };
+        extra_fields.push(ExtraField {
+            field,
+            init,
+            is_phantom,
+        });
     }
-    if fields.len() > 1 {
-        panic!("Currently, outer type with multiple fields is not supported");
-    } else if fields.is_empty() {
-        panic!("Types with no fields are not supported");
+    let field_inner = field_inner.expect("Should never happen: inner field should be found");
+
+    Some((field_inner, extra_fields))
+}
+
+/// Checks whether the field is marked as `#[opaque_typedef(inner)]`.
+fn is_marked_as_inner(field: &syn::Field) -> bool {
+    field
+        .attrs
+        .iter()
+        .filter(|attr| is_attr_with_path(attr, &["opaque_typedef"]))
+        .filter_map(|attr| attr.interpret_meta())
+        .any(|meta| has_word_meta(&meta, &["opaque_typedef", "inner"]))
+}
+
+/// Returns the expression specified by `#[opaque_typedef(default = ..)]`.
+///
+/// Returns `Err(())` if the attribute is specified but invalid.
+fn get_field_default(
+    field: &syn::Field,
+    errors: &mut Vec<syn::Error>,
+) -> Result<Option<syn::Expr>, ()> {
+    let namevalues = field
+        .attrs
+        .iter()
+        .filter(|attr| is_attr_with_path(attr, &["opaque_typedef"]))
+        .filter_map(|attr| attr.interpret_meta())
+        .flat_map(|meta| get_meta_content_by_path(meta, &["opaque_typedef"]))
+        .filter_map(|meta| match meta {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => Some(nv),
+            _ => None,
+        })
+        .filter(|nv| nv.ident == "default")
+        .collect::<Vec<_>>();
+    let nv = match expect_singleton_iter(&namevalues) {
+        SingletonIterResult::None => return Ok(None),
+        SingletonIterResult::Single(nv) => nv,
+        SingletonIterResult::Multiple(first, second) => {
+            errors.push(syn::Error::new_spanned(
+                second,
+                "`#[opaque_typedef(default = ..)]` can be specified at most once for each field",
+            ));
+            first
+        }
+    };
+    match nv.lit {
+        syn::Lit::Str(ref litstr) => parse_litstr::<syn::Expr>(litstr, "default = ..", errors)
+            .map(Some)
+            .ok_or(()),
+        ref lit => {
+            errors.push(syn::Error::new_spanned(
+                lit,
+                format!(
+                    "String value is expected for `#[opaque_typedef(default = ..)]`, \
+                     but got `{}` (invalid type)",
+                    lit.into_token_stream()
+                ),
+            ));
+            Err(())
+        }
+    }
+}
+
+/// Checks whether the type is `PhantomData<_>`.
+///
+/// `PhantomData`, `core::marker::PhantomData`, and `std::marker::PhantomData`
+/// are accepted.
+/// As the bare `PhantomData` can be another type, the type is also checked by
+/// the generated code.
+fn is_phantom_data(ty: &syn::Type) -> bool {
+    let ty_path = match *ty {
+        syn::Type::Path(ref ty_path) if ty_path.qself.is_none() => ty_path,
+        _ => return false,
+    };
+    let idents = ty_path
+        .path
+        .segments
+        .iter()
+        .map(|seg| seg.ident.to_string())
+        .collect::<Vec<_>>();
+    match idents.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["PhantomData"] => ty_path.path.leading_colon.is_none(),
+        ["core", "marker", "PhantomData"] | ["std", "marker", "PhantomData"] => true,
+        _ => false,
     }
-    unreachable!("Currently, outer types with multiple fields are not supported");
 }
 
 fn check_repr_outer(
     ty_outer: &syn::Ident,
     sizedness: Sizedness,
     repr_meta_outer: Option<&syn::Meta>,
+    errors: &mut Vec<syn::Error>,
 ) {
     if sizedness != Sizedness::Unsized {
         // The restriction is necessary only for unsized types.
@@ -68,18 +276,21 @@ fn check_repr_outer(
         }
     }
     // Neither `repr(C)` nor `repr(transparent)` was specified for an unsized type.
-    panic!(
-        "To avoid undefined behavior, outer type `{}` should be marked \
-         as `#[repr(C)]` or `#[repr(transparent)]`.\n\
-         For detail, see <https://github.com/lo48576/opaque_typedef/issues/1>.\n\
-         About `#[repr(transparent)]`, see RFC 1758 \
-         <https://github.com/rust-lang/rfcs/blob/master/text/1758-repr-transparent.md>.\
-         ",
-        ty_outer
-    );
+    errors.push(syn::Error::new_spanned(
+        ty_outer,
+        format!(
+            "To avoid undefined behavior, outer type `{}` should be marked \
+             as `#[repr(C)]` or `#[repr(transparent)]`.\n\
+             For detail, see <https://github.com/lo48576/opaque_typedef/issues/1>.\n\
+             About `#[repr(transparent)]`, see RFC 1758 \
+             <https://github.com/rust-lang/rfcs/blob/master/text/1758-repr-transparent.md>.\
+             ",
+            ty_outer
+        ),
+    ));
 }
 
-fn get_deref_spec(attrs: &[syn::Attribute]) -> DerefSpec {
+fn get_deref_spec(attrs: &[syn::Attribute], errors: &mut Vec<syn::Error>) -> DerefSpec {
     let namevalues = attrs
         .iter()
         .filter(|attr| is_attr_with_path(attr, &["opaque_typedef"]))
@@ -94,63 +305,50 @@ fn get_deref_spec(attrs: &[syn::Attribute]) -> DerefSpec {
     fn get_attr_by_name<'a>(
         namevalues: &'a [syn::MetaNameValue],
         name: &str,
+        errors: &mut Vec<syn::Error>,
     ) -> Option<&'a syn::LitStr> {
-        let iter = namevalues
-            .iter()
-            .filter(|nv| nv.ident == name)
-            .map(|nv| &nv.lit);
-        let lit = expect_singleton_iter(iter)
-            .at_most_one()
-            .unwrap_or_else(|| {
-                panic!(
-                    "`#[opaque_typedef(deref({} = ..))]` can be specified at \
-                     most once for each type",
-                    name
-                )
-            })?;
-        match *lit {
+        let iter = namevalues.iter().filter(|nv| nv.ident == name);
+        let nv = match expect_singleton_iter(iter) {
+            SingletonIterResult::None => return None,
+            SingletonIterResult::Single(nv) => nv,
+            SingletonIterResult::Multiple(first, second) => {
+                errors.push(syn::Error::new_spanned(
+                    second,
+                    format!(
+                        "`#[opaque_typedef(deref({} = ..))]` can be specified \
+                         at most once for each type",
+                        name
+                    ),
+                ));
+                first
+            }
+        };
+        match nv.lit {
             syn::Lit::Str(ref s) => Some(s),
-            ref lit => panic!(
-                "String value is expected for `#[opaque_typedef(deref({} = ..))]`, \
-                 but got `{}` (invalid type)",
-                name,
-                lit.into_token_stream()
-            ),
+            ref lit => {
+                errors.push(syn::Error::new_spanned(
+                    lit,
+                    format!(
+                        "String value is expected for `#[opaque_typedef(deref({} = ..))]`, \
+                         but got `{}` (invalid type)",
+                        name,
+                        lit.into_token_stream()
+                    ),
+                ));
+                None
+            }
         }
     }
 
-    let target = get_attr_by_name(&namevalues, "target");
-    let deref = get_attr_by_name(&namevalues, "deref");
-    let deref_mut = get_attr_by_name(&namevalues, "deref_mut");
-    let ty_deref_target = target.map(|target| {
-        target.parse::<syn::Type>().unwrap_or_else(|e| {
-            panic!(
-                "`#[opaque_typedef(deref(target = ..))]` is specified \
-                 but failed to parse `{}` as type: {}",
-                target.value(),
-                e
-            )
-        })
-    });
-    let fn_name_deref = deref.map(|deref| {
-        deref.parse::<syn::Expr>().unwrap_or_else(|e| {
-            panic!(
-                "`#[opaque_typedef(deref(deref = ..))]` is specified \
-                 but failed to parse `{}` as expression: {}",
-                deref.value(),
-                e
-            )
-        })
-    });
-    let fn_name_deref_mut = deref_mut.map(|deref_mut| {
-        deref_mut.parse::<syn::Expr>().unwrap_or_else(|e| {
-            panic!(
-                "`#[opaque_typedef(deref(deref_mut = ..))]` is \
-                 specified but failed to parse `{}` as expression: {}",
-                deref_mut.value(),
-                e
-            )
-        })
+    let target = get_attr_by_name(&namevalues, "target", errors);
+    let deref = get_attr_by_name(&namevalues, "deref", errors);
+    let deref_mut = get_attr_by_name(&namevalues, "deref_mut", errors);
+    let ty_deref_target =
+        target.and_then(|target| parse_litstr::<syn::Type>(target, "deref(target = ..)", errors));
+    let fn_name_deref =
+        deref.and_then(|deref| parse_litstr::<syn::Expr>(deref, "deref(deref = ..)", errors));
+    let fn_name_deref_mut = deref_mut.and_then(|deref_mut| {
+        parse_litstr::<syn::Expr>(deref_mut, "deref(deref_mut = ..)", errors)
     });
     DerefSpec {
         ty_deref_target,
@@ -167,88 +365,448 @@ fn get_mut_ref_allowed(attrs: &[syn::Attribute]) -> bool {
         .any(|meta| has_word_meta(&meta, &["opaque_typedef", "allow_mut_ref"]))
 }
 
-fn get_validation_spec(attrs: &[syn::Attribute]) -> ValidationSpec {
-    let namevalues = attrs
+/// Returns `not_nan` in `#[opaque_typedef(float(not_nan))]`, if specified.
+fn get_float_not_nan(attrs: &[syn::Attribute], errors: &mut Vec<syn::Error>) -> Option<syn::Ident> {
+    let mut not_nan = None;
+    let nested_metas = attrs
+        .iter()
+        .filter(|attr| is_attr_with_path(attr, &["opaque_typedef"]))
+        .filter_map(|attr| attr.interpret_meta())
+        .flat_map(|meta| get_meta_content_by_path(meta, &["opaque_typedef", "float"]));
+    for nested_meta in nested_metas {
+        match nested_meta {
+            syn::NestedMeta::Meta(syn::Meta::Word(ref ident)) if ident == "not_nan" => {
+                if not_nan.is_none() {
+                    not_nan = Some(ident.clone());
+                }
+            }
+            ref nested_meta => errors.push(syn::Error::new_spanned(
+                nested_meta,
+                format!(
+                    "Unsupported float preset `{}`, expected `not_nan`",
+                    nested_meta.into_token_stream()
+                ),
+            )),
+        }
+    }
+    not_nan
+}
+
+fn get_validation_spec(
+    attrs: &[syn::Attribute],
+    ty_outer: &syn::Ident,
+    generics: &syn::Generics,
+    sizedness: Sizedness,
+    float_not_nan: Option<&syn::Ident>,
+    errors: &mut Vec<syn::Error>,
+) -> ValidationSpec {
+    let metas = attrs
         .iter()
         .filter(|attr| is_attr_with_path(attr, &["opaque_typedef"]))
         .filter_map(|attr| attr.interpret_meta())
         .flat_map(|meta| get_meta_content_by_path(meta, &["opaque_typedef", "validation"]))
-        .filter_map(|meta| match meta {
-            syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => Some(nv),
+        .filter_map(|nested_meta| match nested_meta {
+            syn::NestedMeta::Meta(meta) => Some(meta),
+            syn::NestedMeta::Literal(..) => None,
+        })
+        .collect::<Vec<_>>();
+    let namevalues = metas
+        .iter()
+        .filter_map(|meta| match *meta {
+            syn::Meta::NameValue(ref nv) => Some(nv.clone()),
             _ => None,
         })
         .collect::<Vec<_>>();
+    let keep_value = metas
+        .iter()
+        .filter_map(|meta| match *meta {
+            syn::Meta::Word(ref ident) if ident == "keep_value" => Some(ident),
+            _ => None,
+        })
+        .next();
+    let rejected = metas
+        .iter()
+        .filter_map(|meta| match *meta {
+            syn::Meta::Word(ref ident) if ident == "rejected" => Some(ident),
+            _ => None,
+        })
+        .next();
 
     fn get_attr_by_name<'a>(
         namevalues: &'a [syn::MetaNameValue],
         name: &str,
+        errors: &mut Vec<syn::Error>,
     ) -> Option<&'a syn::LitStr> {
-        let iter = namevalues
-            .iter()
-            .filter(|nv| nv.ident == name)
-            .map(|nv| &nv.lit);
-        let lit = expect_singleton_iter(iter)
-            .at_most_one()
-            .unwrap_or_else(|| {
-                panic!(
-                    "`#[opaque_typedef(validation({} = ..))]` can be specified \
-                     at most once for each type",
-                    name
-                )
-            })?;
-        match *lit {
+        let iter = namevalues.iter().filter(|nv| nv.ident == name);
+        let nv = match expect_singleton_iter(iter) {
+            SingletonIterResult::None => return None,
+            SingletonIterResult::Single(nv) => nv,
+            SingletonIterResult::Multiple(first, second) => {
+                errors.push(syn::Error::new_spanned(
+                    second,
+                    format!(
+                        "`#[opaque_typedef(validation({} = ..))]` can be specified \
+                         at most once for each type",
+                        name
+                    ),
+                ));
+                first
+            }
+        };
+        match nv.lit {
             syn::Lit::Str(ref s) => Some(s),
-            ref lit => panic!(
-                "String value is expected for `#[opaque_typedef(validation({} = ..))]`, \
-                 but got `{}` (invalid type)",
-                name,
-                lit.into_token_stream()
-            ),
+            ref lit => {
+                errors.push(syn::Error::new_spanned(
+                    lit,
+                    format!(
+                        "String value is expected for `#[opaque_typedef(validation({} = ..))]`, \
+                         but got `{}` (invalid type)",
+                        name,
+                        lit.into_token_stream()
+                    ),
+                ));
+                None
+            }
         }
     }
 
-    let fn_validator = get_attr_by_name(&namevalues, "validator").map(|litstr| {
-        litstr.parse::<syn::Expr>().unwrap_or_else(|e| {
-            panic!(
-                "`#[opaque_typedef(validation(validator = ..))]` is specified \
-                 but failed to parse `{}` as expression: {}",
-                litstr.value(),
-                e
-            )
-        })
-    });
-    let ty_error = get_attr_by_name(&namevalues, "error_type").map(|litstr| {
-        litstr.parse::<syn::Type>().unwrap_or_else(|e| {
-            panic!(
-                "`#[opaque_typedef(validation(error_type = ..))]` is specified \
-                 but failed to parse `{}` as expression: {}",
-                litstr.value(),
-                e
-            )
-        })
-    });
-    let error_msg = get_attr_by_name(&namevalues, "error_msg").map(|litstr| litstr.value());
-
-    match (fn_validator.is_some(), ty_error.is_some()) {
-        (true, false) => panic!(
-            "`#[opaque_typedef(validation(validator = ..))]` is specified but \
-             `#[opaque_typedef(validation(error_type = ..))]` is not found"
-        ),
-        (false, true) => panic!(
+    let validator = get_attr_by_name(&namevalues, "validator", errors);
+    let error_type = get_attr_by_name(&namevalues, "error_type", errors);
+    let error = get_attr_by_name(&namevalues, "error", errors);
+    let message = get_attr_by_name(&namevalues, "message", errors);
+    let check = get_attr_by_name(&namevalues, "check", errors);
+    let with = get_attr_by_name(&namevalues, "with", errors);
+    let normalize = get_attr_by_name(&namevalues, "normalize", errors);
+    let fn_normalize = normalize
+        .and_then(|litstr| parse_litstr::<syn::Expr>(litstr, "validation(normalize = ..)", errors));
+    let not_normalized = get_attr_by_name(&namevalues, "not_normalized", errors);
+    // Unsized types cannot normalize values given by reference, so such
+    // values are rejected if they are not normalized.
+    match (sizedness, normalize, not_normalized) {
+        (Sizedness::Sized, _, Some(litstr)) => errors.push(syn::Error::new_spanned(
+            litstr,
+            "`#[opaque_typedef(validation(not_normalized = ..))]` is not supported for sized types",
+        )),
+        (Sizedness::Unsized, None, Some(litstr)) => errors.push(syn::Error::new_spanned(
+            litstr,
+            "`#[opaque_typedef(validation(not_normalized = ..))]` requires \
+             `#[opaque_typedef(validation(normalize = ..))]`",
+        )),
+        (Sizedness::Unsized, Some(_), Some(litstr)) if error.is_some() => {
+            errors.push(syn::Error::new_spanned(
+                litstr,
+                "`#[opaque_typedef(validation(not_normalized = ..))]` cannot be used with \
+                 `#[opaque_typedef(validation(error = \"auto\"))]`",
+            ))
+        }
+        (Sizedness::Unsized, Some(litstr), None) if error.is_none() => {
+            errors.push(syn::Error::new_spanned(
+                litstr,
+                "`#[opaque_typedef(validation(normalize = ..))]` for unsized types requires \
+                 `#[opaque_typedef(validation(not_normalized = ..))]` to reject values \
+                 which are not normalized",
+            ))
+        }
+        _ => {}
+    }
+    let fn_not_normalized = not_normalized
+        .filter(|_| sizedness == Sizedness::Unsized && normalize.is_some() && error.is_none())
+        .and_then(|litstr| {
+            parse_litstr::<syn::Expr>(litstr, "validation(not_normalized = ..)", errors)
+        });
+    if float_not_nan.is_some() {
+        // `float(not_nan)` installs its own validator.
+        for litstr in validator
+            .iter()
+            .chain(error_type.iter())
+            .chain(error.iter())
+            .chain(check.iter())
+            .chain(with.iter())
+        {
+            errors.push(syn::Error::new_spanned(
+                litstr,
+                "`#[opaque_typedef(validation(validator = ..))]`, \
+                 `#[opaque_typedef(validation(check = ..))]`, \
+                 `#[opaque_typedef(validation(with = ..))]`, \
+                 `#[opaque_typedef(validation(error_type = ..))]`, and \
+                 `#[opaque_typedef(validation(error = ..))]` cannot be used with \
+                 `#[opaque_typedef(float(not_nan))]`",
+            ));
+        }
+        if let Some(ident) = rejected {
+            errors.push(syn::Error::new_spanned(
+                ident,
+                "`#[opaque_typedef(validation(rejected))]` cannot be used with \
+                 `#[opaque_typedef(float(not_nan))]`",
+            ));
+        }
+        let error_msg =
+            get_attr_by_name(&namevalues, "error_msg", errors).map(|litstr| litstr.value());
+        return ValidationSpec {
+            fn_validator: Some(
+                syn::parse_str("::opaque_typedef::float::reject_nan")
+                    .expect("Failed to generate validator path"),
+            ),
+            fn_check: None,
+            validator_with: None,
+            fn_normalize,
+            fn_not_normalized: None,
+            ty_error: Some(
+                syn::parse_str("::opaque_typedef::float::NanError")
+                    .expect("Failed to generate error type"),
+            ),
+            error_msg,
+            auto_error: None,
+            rejected: false,
+        };
+    }
+    if let Some(litstr) = error {
+        if let Some(litstr) = error_type {
+            errors.push(syn::Error::new_spanned(
+                litstr,
+                "`#[opaque_typedef(validation(error_type = ..))]` cannot be used with \
+                 `#[opaque_typedef(validation(error = \"auto\"))]`",
+            ));
+        }
+        if let (Some(ident), Sizedness::Unsized) = (keep_value, sizedness) {
+            errors.push(syn::Error::new_spanned(
+                ident,
+                "`#[opaque_typedef(validation(keep_value))]` is not supported for unsized types",
+            ));
+        }
+        if let Some(ident) = rejected {
+            errors.push(syn::Error::new_spanned(
+                ident,
+                "`#[opaque_typedef(validation(rejected))]` cannot be used with \
+                 `#[opaque_typedef(validation(error = \"auto\"))]`",
+            ));
+        }
+        for (name, litstr) in [("check", check), ("with", with)].iter() {
+            if let Some(litstr) = *litstr {
+                errors.push(syn::Error::new_spanned(
+                    litstr,
+                    format!(
+                        "`#[opaque_typedef(validation({} = ..))]` cannot be used with \
+                         `#[opaque_typedef(validation(error = \"auto\"))]`",
+                        name
+                    ),
+                ));
+            }
+        }
+        let error_msg =
+            get_attr_by_name(&namevalues, "error_msg", errors).map(|litstr| litstr.value());
+        let mut spec = get_validation_spec_auto_error(
+            litstr,
+            ty_outer,
+            validator,
+            message,
+            keep_value.is_some() && sizedness == Sizedness::Sized,
+            error_msg,
+            errors,
+        );
+        // The generated validator for sized types hands the rejected value
+        // back, unless the error holds the value.
+        spec.rejected = match (sizedness, spec.auto_error.as_ref()) {
+            (Sizedness::Sized, Some(auto)) => !auto.keep_value,
+            _ => false,
+        };
+        spec.fn_normalize = fn_normalize;
+        if sizedness == Sizedness::Sized && spec.auto_error.is_some() {
+            spec.fn_check = Some(
+                syn::parse_str::<syn::Expr>(&format!("{}::__opaque_typedef_check", ty_outer))
+                    .expect("Failed to generate checker path"),
+            );
+        }
+        return spec;
+    }
+    if let Some(litstr) = message {
+        errors.push(syn::Error::new_spanned(
+            litstr,
+            "`#[opaque_typedef(validation(message = ..))]` requires \
+             `#[opaque_typedef(validation(error = \"auto\"))]`",
+        ));
+    }
+    if let Some(ident) = keep_value {
+        errors.push(syn::Error::new_spanned(
+            ident,
+            "`#[opaque_typedef(validation(keep_value))]` requires \
+             `#[opaque_typedef(validation(error = \"auto\"))]`",
+        ));
+    }
+    if let (Some(litstr), Some(_)) = (check, validator) {
+        errors.push(syn::Error::new_spanned(
+            litstr,
+            "`#[opaque_typedef(validation(check = ..))]` cannot be used with \
+             `#[opaque_typedef(validation(validator = ..))]`",
+        ));
+    }
+    if let (Some(litstr), true) = (with, validator.is_some() || check.is_some()) {
+        errors.push(syn::Error::new_spanned(
+            litstr,
+            "`#[opaque_typedef(validation(with = ..))]` cannot be used with \
+             `#[opaque_typedef(validation(validator = ..))]` or \
+             `#[opaque_typedef(validation(check = ..))]`",
+        ));
+    }
+    // `not_normalized` also requires the error type.
+    let validator_names = [
+        ("validator", validator),
+        ("check", check),
+        ("with", with),
+        ("not_normalized", not_normalized),
+    ];
+    let specified_validator = validator_names
+        .iter()
+        .find(|&&(_, litstr)| litstr.is_some());
+    match (specified_validator, error_type) {
+        (Some(&(name, Some(litstr))), None) => errors.push(syn::Error::new_spanned(
+            litstr,
+            format!(
+                "`#[opaque_typedef(validation({} = ..))]` is specified but \
+                 `#[opaque_typedef(validation(error_type = ..))]` is not found",
+                name
+            ),
+        )),
+        (None, Some(litstr)) => errors.push(syn::Error::new_spanned(
+            litstr,
             "`#[opaque_typedef(validation(error_type = ..))]` is specified but \
-             `#[opaque_typedef(validation(validator = ..))]` is not found"
-        ),
+             `#[opaque_typedef(validation(validator = ..))]`, \
+             `#[opaque_typedef(validation(check = ..))]`, or \
+             `#[opaque_typedef(validation(with = ..))]` is not found",
+        )),
         _ => {}
     }
+    let mut fn_validator = validator
+        .and_then(|litstr| parse_litstr::<syn::Expr>(litstr, "validation(validator = ..)", errors));
+    let validator_with = with
+        .filter(|_| validator.is_none() && check.is_none())
+        .and_then(|litstr| parse_litstr::<syn::Expr>(litstr, "validation(with = ..)", errors));
+    let mut fn_check = check
+        .filter(|_| validator.is_none())
+        .and_then(|litstr| parse_litstr::<syn::Expr>(litstr, "validation(check = ..)", errors));
+    if validator_with.is_some() {
+        // The checker is generated from the validator value as an associated
+        // function of the outer type.
+        let (_, type_generics, _) = generics.split_for_impl();
+        fn_check = Some(
+            syn::parse2::<syn::Expr>(quote!(<#ty_outer #type_generics>::__opaque_typedef_check))
+                .expect("Failed to generate checker path"),
+        );
+    }
+    if fn_check.is_some() {
+        // The validator is generated from the checker as an associated
+        // function of the outer type.
+        let (_, type_generics, _) = generics.split_for_impl();
+        fn_validator = Some(
+            syn::parse2::<syn::Expr>(quote!(<#ty_outer #type_generics>::__opaque_typedef_validate))
+                .expect("Failed to generate validator path"),
+        );
+    }
+    let ty_error = error_type.and_then(|litstr| {
+        parse_litstr::<syn::Type>(litstr, "validation(error_type = ..)", errors)
+    });
+    let error_msg = get_attr_by_name(&namevalues, "error_msg", errors).map(|litstr| litstr.value());
+    if let Some(ident) = rejected {
+        if sizedness == Sizedness::Unsized {
+            errors.push(syn::Error::new_spanned(
+                ident,
+                "`#[opaque_typedef(validation(rejected))]` is not supported for unsized types",
+            ));
+        } else if fn_validator.is_none() {
+            errors.push(syn::Error::new_spanned(
+                ident,
+                "`#[opaque_typedef(validation(rejected))]` requires \
+                 `#[opaque_typedef(validation(validator = ..))]`",
+            ));
+        }
+    }
+
+    // The generated validator for sized types hands the rejected value back.
+    let rejected = (rejected.is_some() || fn_check.is_some()) && sizedness == Sizedness::Sized;
 
     ValidationSpec {
         fn_validator,
+        fn_check,
+        validator_with,
+        fn_normalize,
+        fn_not_normalized,
         ty_error,
         error_msg,
+        auto_error: None,
+        rejected,
     }
 }
 
-fn get_cmp_spec(attrs: &[syn::Attribute]) -> CmpSpec {
+/// Returns validation spec with the generated error type
+/// (`#[opaque_typedef(validation(error = "auto"))]`).
+fn get_validation_spec_auto_error(
+    error: &syn::LitStr,
+    ty_outer: &syn::Ident,
+    validator: Option<&syn::LitStr>,
+    message: Option<&syn::LitStr>,
+    keep_value: bool,
+    error_msg: Option<String>,
+    errors: &mut Vec<syn::Error>,
+) -> ValidationSpec {
+    if error.value() != "auto" {
+        errors.push(syn::Error::new_spanned(
+            error,
+            format!(
+                "Unsupported `#[opaque_typedef(validation(error = {:?}))]`, \
+                 expected `error = \"auto\"`",
+                error.value()
+            ),
+        ));
+    }
+    let fn_predicate = match validator {
+        Some(litstr) => parse_litstr::<syn::Expr>(litstr, "validation(validator = ..)", errors),
+        None => {
+            errors.push(syn::Error::new_spanned(
+                error,
+                "`#[opaque_typedef(validation(error = \"auto\"))]` is specified but \
+                 `#[opaque_typedef(validation(validator = ..))]` is not found",
+            ));
+            None
+        }
+    };
+    let message = message.map_or_else(
+        || format!("invalid `{}` value", ty_outer),
+        |litstr| litstr.value(),
+    );
+    let ty_error = syn::Ident::new(&format!("{}Error", ty_outer), ty_outer.span());
+    // The validator is generated as an associated function of the outer type.
+    let fn_validator =
+        syn::parse_str::<syn::Expr>(&format!("{}::__opaque_typedef_validate", ty_outer))
+            .expect("Failed to generate validator path");
+    let error_msg = error_msg.unwrap_or_else(|| message.clone());
+    let auto_error = fn_predicate.map(|fn_predicate| AutoErrorSpec {
+        span: error.span(),
+        ty_error: ty_error.clone(),
+        fn_predicate,
+        message,
+        keep_value,
+    });
+    ValidationSpec {
+        fn_validator: Some(fn_validator),
+        ty_error: Some(
+            syn::parse2::<syn::Type>(ty_error.into_token_stream())
+                .expect("Failed to generate error type"),
+        ),
+        fn_check: None,
+        validator_with: None,
+        fn_normalize: None,
+        fn_not_normalized: None,
+        error_msg: Some(error_msg),
+        auto_error,
+        rejected: false,
+    }
+}
+
+fn get_cmp_spec(
+    attrs: &[syn::Attribute],
+    float_not_nan: Option<&syn::Ident>,
+    errors: &mut Vec<syn::Error>,
+) -> CmpSpec {
     let namevalues = attrs
         .iter()
         .filter(|attr| is_attr_with_path(attr, &["opaque_typedef"]))
@@ -263,68 +821,747 @@ fn get_cmp_spec(attrs: &[syn::Attribute]) -> CmpSpec {
     fn get_attr_by_name<'a>(
         namevalues: &'a [syn::MetaNameValue],
         name: &str,
+        errors: &mut Vec<syn::Error>,
     ) -> Option<&'a syn::LitStr> {
-        let iter = namevalues
-            .iter()
-            .filter(|nv| nv.ident == name)
-            .map(|nv| &nv.lit);
-        let lit = expect_singleton_iter(iter)
-            .at_most_one()
-            .unwrap_or_else(|| {
-                panic!(
-                    "`#[opaque_typedef(cmp({} = ..))]` can be specified \
-                     at most once for each type",
-                    name
-                )
-            })?;
-        match *lit {
+        let iter = namevalues.iter().filter(|nv| nv.ident == name);
+        let nv = match expect_singleton_iter(iter) {
+            SingletonIterResult::None => return None,
+            SingletonIterResult::Single(nv) => nv,
+            SingletonIterResult::Multiple(first, second) => {
+                errors.push(syn::Error::new_spanned(
+                    second,
+                    format!(
+                        "`#[opaque_typedef(cmp({} = ..))]` can be specified \
+                         at most once for each type",
+                        name
+                    ),
+                ));
+                first
+            }
+        };
+        match nv.lit {
             syn::Lit::Str(ref s) => Some(s),
-            ref lit => panic!(
-                "String value is expected for `#[opaque_typedef(cmp({} = ..))]`, \
-                 but got `{}` (invalid type)",
-                name,
-                lit.into_token_stream()
-            ),
+            ref lit => {
+                errors.push(syn::Error::new_spanned(
+                    lit,
+                    format!(
+                        "String value is expected for `#[opaque_typedef(cmp({} = ..))]`, \
+                         but got `{}` (invalid type)",
+                        name,
+                        lit.into_token_stream()
+                    ),
+                ));
+                None
+            }
         }
     }
 
-    let partial_eq = get_attr_by_name(&namevalues, "partial_eq").map(|litstr| {
-        litstr.parse::<syn::Expr>().unwrap_or_else(|e| {
-            panic!(
-                "`#[opaque_typedef(cmp(partial_eq = ..))]` is specified \
-                 but failed to parse `{}` as expression: {}",
-                litstr.value(),
-                e
-            )
-        })
-    });
+    let partial_eq = get_attr_by_name(&namevalues, "partial_eq", errors)
+        .and_then(|litstr| parse_litstr::<syn::Expr>(litstr, "cmp(partial_eq = ..)", errors));
 
-    let partial_ord = get_attr_by_name(&namevalues, "partial_ord").map(|litstr| {
-        litstr.parse::<syn::Expr>().unwrap_or_else(|e| {
-            panic!(
-                "`#[opaque_typedef(cmp(partial_ord = ..))]` is specified \
-                 but failed to parse `{}` as expression: {}",
-                litstr.value(),
-                e
-            )
-        })
-    });
+    let partial_ord = get_attr_by_name(&namevalues, "partial_ord", errors)
+        .and_then(|litstr| parse_litstr::<syn::Expr>(litstr, "cmp(partial_ord = ..)", errors));
+
+    let ord = get_attr_by_name(&namevalues, "ord", errors)
+        .and_then(|litstr| parse_litstr::<syn::Expr>(litstr, "cmp(ord = ..)", errors));
+
+    let hash = get_attr_by_name(&namevalues, "hash", errors)
+        .and_then(|litstr| parse_litstr::<syn::Expr>(litstr, "cmp(hash = ..)", errors));
+
+    let allow_default_hash = attrs
+        .iter()
+        .filter(|attr| is_attr_with_path(attr, &["opaque_typedef"]))
+        .filter_map(|attr| attr.interpret_meta())
+        .any(|meta| has_word_meta(&meta, &["opaque_typedef", "cmp", "allow_default_hash"]));
 
-    let ord = get_attr_by_name(&namevalues, "ord").map(|litstr| {
-        litstr.parse::<syn::Expr>().unwrap_or_else(|e| {
-            panic!(
-                "`#[opaque_typedef(cmp(ord = ..))]` is specified \
-                 but failed to parse `{}` as expression: {}",
-                litstr.value(),
-                e
-            )
+    let key = get_attr_by_name(&namevalues, "key", errors)
+        .and_then(|litstr| parse_litstr::<syn::Expr>(litstr, "cmp(key = ..)", errors));
+
+    let words = attrs
+        .iter()
+        .filter(|attr| is_attr_with_path(attr, &["opaque_typedef"]))
+        .filter_map(|attr| attr.interpret_meta())
+        .flat_map(|meta| get_meta_content_by_path(meta, &["opaque_typedef", "cmp"]))
+        .filter_map(|meta| match meta {
+            syn::NestedMeta::Meta(syn::Meta::Word(ident)) => Some(ident),
+            _ => None,
         })
-    });
+        .collect::<Vec<_>>();
+    let ascii_case_insensitive = words
+        .iter()
+        .find(|ident| *ident == "ascii_case_insensitive")
+        .cloned();
+    let constant_time = words
+        .iter()
+        .find(|ident| *ident == "constant_time")
+        .cloned();
+
+    // Presets determine all the comparators and the hash function, so they
+    // cannot be specified together.
+    let mut presets = Vec::new();
+    if let Some(ref key) = key {
+        presets.push(("cmp(key = ..)", key.into_token_stream()));
+    }
+    if let Some(ref ident) = ascii_case_insensitive {
+        presets.push(("cmp(ascii_case_insensitive)", ident.into_token_stream()));
+    }
+    if let Some(ident) = float_not_nan {
+        presets.push(("float(not_nan)", ident.into_token_stream()));
+    }
+    // `constant_time` only replaces the equality comparator, but it cannot be
+    // used with other comparators either.
+    if let Some(ref ident) = constant_time {
+        presets.push(("cmp(constant_time)", ident.into_token_stream()));
+    }
+    if let Some(&(preset, _)) = presets.first() {
+        for &(name, ref tokens) in &presets[1..] {
+            errors.push(syn::Error::new_spanned(
+                tokens,
+                format!(
+                    "`#[opaque_typedef({})]` cannot be used with `#[opaque_typedef({})]`",
+                    name, preset
+                ),
+            ));
+        }
+        let others = [
+            ("partial_eq", &partial_eq),
+            ("partial_ord", &partial_ord),
+            ("ord", &ord),
+            ("hash", &hash),
+        ];
+        for &(name, expr) in &others {
+            if let Some(ref expr) = *expr {
+                errors.push(syn::Error::new_spanned(
+                    expr,
+                    format!(
+                        "`#[opaque_typedef(cmp({} = ..))]` cannot be used with \
+                         `#[opaque_typedef({})]`",
+                        name, preset
+                    ),
+                ));
+            }
+        }
+    }
 
     CmpSpec {
         partial_eq,
         partial_ord,
         ord,
+        hash,
+        allow_default_hash,
+        key,
+        ascii_case_insensitive,
+        float_not_nan: float_not_nan.cloned(),
+        constant_time,
+    }
+}
+
+fn get_ops_spec(
+    attrs: &[syn::Attribute],
+    sizedness: Sizedness,
+    counterpart: Option<&syn::Type>,
+    validation_spec: &ValidationSpec,
+    is_mut_ref_allowed: bool,
+    errors: &mut Vec<syn::Error>,
+) -> OpsSpec {
+    let namevalues = attrs
+        .iter()
+        .filter(|attr| is_attr_with_path(attr, &["opaque_typedef"]))
+        .filter_map(|attr| attr.interpret_meta())
+        .flat_map(|meta| get_meta_content_by_path(meta, &["opaque_typedef", "ops"]))
+        .filter_map(|meta| match meta {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => Some(nv),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let unsized_output = get_unsized_op_output(&namevalues, sizedness, counterpart, errors);
+    let on_invalid = get_on_invalid(&namevalues, sizedness, errors);
+    let assign_ops = get_assign_ops(attrs, validation_spec, is_mut_ref_allowed, errors);
+    let unsized_fns = get_unsized_op_fns(&namevalues, sizedness, errors);
+
+    OpsSpec {
+        unsized_output,
+        on_invalid,
+        assign_ops,
+        unsized_fns,
+    }
+}
+
+/// Returns the string value of `#[opaque_typedef(ops(name = ..))]`.
+fn get_ops_attr_by_name<'a>(
+    namevalues: &'a [syn::MetaNameValue],
+    name: &str,
+    errors: &mut Vec<syn::Error>,
+) -> Option<(&'a syn::MetaNameValue, &'a syn::LitStr)> {
+    let iter = namevalues.iter().filter(|nv| nv.ident == name);
+    let nv = match expect_singleton_iter(iter) {
+        SingletonIterResult::None => return None,
+        SingletonIterResult::Single(nv) => nv,
+        SingletonIterResult::Multiple(first, second) => {
+            errors.push(syn::Error::new_spanned(
+                second,
+                format!(
+                    "`#[opaque_typedef(ops({} = ..))]` can be specified \
+                     at most once for each type",
+                    name
+                ),
+            ));
+            first
+        }
+    };
+    match nv.lit {
+        syn::Lit::Str(ref litstr) => Some((nv, litstr)),
+        ref lit => {
+            errors.push(syn::Error::new_spanned(
+                lit,
+                format!(
+                    "String value is expected for `#[opaque_typedef(ops({} = ..))]`, \
+                     but got `{}` (invalid type)",
+                    name,
+                    lit.into_token_stream()
+                ),
+            ));
+            None
+        }
+    }
+}
+
+/// Returns the output of operators for unsized types.
+fn get_unsized_op_output(
+    namevalues: &[syn::MetaNameValue],
+    sizedness: Sizedness,
+    counterpart: Option<&syn::Type>,
+    errors: &mut Vec<syn::Error>,
+) -> UnsizedOpOutput {
+    let default = if counterpart.is_some() {
+        UnsizedOpOutput::Owned
+    } else {
+        UnsizedOpOutput::Inner
+    };
+    let (nv, litstr) = match get_ops_attr_by_name(namevalues, "output", errors) {
+        Some(v) => v,
+        None => return default,
+    };
+    if sizedness == Sizedness::Sized {
+        errors.push(syn::Error::new_spanned(
+            nv,
+            "`#[opaque_typedef(ops(output = ..))]` is not supported for sized types, \
+             because the output of operators is always the outer type",
+        ));
+        return default;
+    }
+    match litstr.value().parse() {
+        Ok(UnsizedOpOutput::Owned) if counterpart.is_none() => {
+            errors.push(syn::Error::new_spanned(
+                litstr,
+                "`#[opaque_typedef(ops(output = \"owned\"))]` requires \
+                 `#[opaque_typedef(owned = ..)]`, but not specified",
+            ));
+            default
+        }
+        Ok(v) => v,
+        Err(_) => {
+            errors.push(syn::Error::new_spanned(
+                litstr,
+                format!(
+                    "Unknown value for `#[opaque_typedef(ops(output = ..))]`: {:?} \
+                     (expected \"inner\" or \"owned\")",
+                    litstr.value()
+                ),
+            ));
+            default
+        }
+    }
+}
+
+/// Method names of operators which can have custom functions for unsized types.
+const UNSIZED_OP_FN_NAMES: &[&str] = &[
+    "add", "bitand", "bitor", "bitxor", "div", "mul", "neg", "not", "rem", "shl", "shr", "sub",
+];
+
+/// Returns the functions to compute the results of operators for unsized types.
+fn get_unsized_op_fns(
+    namevalues: &[syn::MetaNameValue],
+    sizedness: Sizedness,
+    errors: &mut Vec<syn::Error>,
+) -> Vec<(&'static str, syn::Expr)> {
+    let mut fns = Vec::new();
+    for &name in UNSIZED_OP_FN_NAMES {
+        let (nv, litstr) = match get_ops_attr_by_name(namevalues, name, errors) {
+            Some(v) => v,
+            None => continue,
+        };
+        if sizedness == Sizedness::Sized {
+            errors.push(syn::Error::new_spanned(
+                nv,
+                format!(
+                    "`#[opaque_typedef(ops({} = ..))]` is not supported for sized types",
+                    name
+                ),
+            ));
+            continue;
+        }
+        let attr = format!("ops({} = ..)", name);
+        if let Some(expr) = parse_litstr::<syn::Expr>(litstr, &attr, errors) {
+            fns.push((name, expr));
+        }
+    }
+    fns
+}
+
+/// Policies of `#[opaque_typedef(ops(on_invalid = ..))]` which take functions,
+/// and the names of the attributes to specify the functions.
+const ON_INVALID_FNS: &[(&str, &str)] = &[("saturate", "clamp"), ("wrap", "wrap")];
+
+/// Returns the policy for operator results which fail validation.
+fn get_on_invalid(
+    namevalues: &[syn::MetaNameValue],
+    sizedness: Sizedness,
+    errors: &mut Vec<syn::Error>,
+) -> OnInvalidOpResult {
+    let on_invalid = get_ops_attr_by_name(namevalues, "on_invalid", errors);
+    let mut fns = Vec::new();
+    for &(policy, name) in ON_INVALID_FNS {
+        let attr = format!("ops({} = ..)", name);
+        let f = get_ops_attr_by_name(namevalues, name, errors).and_then(|(nv, litstr)| {
+            parse_litstr::<syn::Expr>(litstr, &attr, errors).map(|expr| (nv, expr))
+        });
+        if let Some((nv, expr)) = f {
+            fns.push((policy, name, nv, expr));
+        }
+    }
+    let (nv, litstr) = match on_invalid {
+        Some(v) => v,
+        None => {
+            for &(policy, name, nv, _) in &fns {
+                errors.push(syn::Error::new_spanned(
+                    nv,
+                    format!(
+                        "`#[opaque_typedef(ops({} = ..))]` requires \
+                         `#[opaque_typedef(ops(on_invalid = \"{}\"))]`, but not specified",
+                        name, policy
+                    ),
+                ));
+            }
+            return OnInvalidOpResult::Panic;
+        }
+    };
+    if sizedness == Sizedness::Unsized {
+        errors.push(syn::Error::new_spanned(
+            nv,
+            "`#[opaque_typedef(ops(on_invalid = ..))]` is not supported for unsized types",
+        ));
+        return OnInvalidOpResult::Panic;
+    }
+    let value = litstr.value();
+    let mismatched = fns
+        .iter()
+        .filter(|&&(policy, ..)| policy != value)
+        .collect::<Vec<_>>();
+    for &&(policy, name, nv, _) in &mismatched {
+        errors.push(syn::Error::new_spanned(
+            nv,
+            format!(
+                "`#[opaque_typedef(ops({} = ..))]` can be used only with \
+                 `#[opaque_typedef(ops(on_invalid = \"{}\"))]`",
+                name, policy
+            ),
+        ));
+    }
+    if !mismatched.is_empty() {
+        return OnInvalidOpResult::Panic;
+    }
+    let f = fns.into_iter().next().map(|(_, _, _, expr)| expr);
+    match (value.as_str(), f) {
+        ("panic", _) => OnInvalidOpResult::Panic,
+        ("checked", _) => OnInvalidOpResult::Checked,
+        ("saturate", Some(expr)) => OnInvalidOpResult::Saturate(expr),
+        ("wrap", Some(expr)) => OnInvalidOpResult::Wrap(expr),
+        (value, _) => {
+            let message = match ON_INVALID_FNS.iter().find(|&&(policy, _)| policy == value) {
+                Some(&(_, name)) => format!(
+                    "`#[opaque_typedef(ops(on_invalid = \"{}\"))]` requires \
+                     `#[opaque_typedef(ops({} = ..))]`, but not specified",
+                    value, name
+                ),
+                None => format!(
+                    "Unknown value for `#[opaque_typedef(ops(on_invalid = ..))]`: {:?} \
+                     (expected \"panic\", \"checked\", \"saturate\", or \"wrap\")",
+                    value
+                ),
+            };
+            errors.push(syn::Error::new_spanned(litstr, message));
+            OnInvalidOpResult::Panic
+        }
+    }
+}
+
+/// Returns the strategy to implement `*Assign` operators.
+fn get_assign_ops(
+    attrs: &[syn::Attribute],
+    validation_spec: &ValidationSpec,
+    is_mut_ref_allowed: bool,
+    errors: &mut Vec<syn::Error>,
+) -> AssignOpsStrategy {
+    let has_validator = validation_spec.fn_validator.is_some();
+    let default = if has_validator {
+        AssignOpsStrategy::ViaBinop
+    } else {
+        AssignOpsStrategy::InPlace
+    };
+    let namevalues = attrs
+        .iter()
+        .filter(|attr| is_attr_with_path(attr, &["opaque_typedef"]))
+        .filter_map(|attr| attr.interpret_meta())
+        .flat_map(|meta| get_meta_content_by_path(meta, &["opaque_typedef"]))
+        .filter_map(|meta| match meta {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => Some(nv),
+            _ => None,
+        })
+        .filter(|nv| nv.ident == "assign_ops")
+        .collect::<Vec<_>>();
+    let nv = match expect_singleton_iter(&namevalues) {
+        SingletonIterResult::None => return default,
+        SingletonIterResult::Single(nv) => nv,
+        SingletonIterResult::Multiple(first, second) => {
+            errors.push(syn::Error::new_spanned(
+                second,
+                "`#[opaque_typedef(assign_ops = ..)]` can be specified at most once for each type",
+            ));
+            first
+        }
+    };
+    let litstr = match nv.lit {
+        syn::Lit::Str(ref s) => s,
+        ref lit => {
+            errors.push(syn::Error::new_spanned(
+                lit,
+                format!(
+                    "String value is expected for `#[opaque_typedef(assign_ops = ..)]`, \
+                     but got `{}` (invalid type)",
+                    lit.into_token_stream()
+                ),
+            ));
+            return default;
+        }
+    };
+    match litstr.value().parse() {
+        Ok(AssignOpsStrategy::InPlace) if has_validator && !is_mut_ref_allowed => {
+            errors.push(syn::Error::new_spanned(
+                litstr,
+                "`#[opaque_typedef(assign_ops = \"in_place\")]` modifies the inner value \
+                 without validation, so it requires `#[opaque_typedef(allow_mut_ref)]` \
+                 for types with validator",
+            ));
+            AssignOpsStrategy::InPlace
+        }
+        Ok(v) => v,
+        Err(_) => {
+            errors.push(syn::Error::new_spanned(
+                litstr,
+                format!(
+                    "Unknown value for `#[opaque_typedef(assign_ops = ..)]`: {:?} \
+                     (expected \"via_binop\" or \"in_place\")",
+                    litstr.value()
+                ),
+            ));
+            default
+        }
+    }
+}
+
+/// Returns secret mode spec if `#[opaque_typedef(secret)]` or
+/// `#[opaque_typedef(secret(..))]` is specified.
+fn get_secret_spec(
+    attrs: &[syn::Attribute],
+    generics: &syn::Generics,
+    sizedness: Sizedness,
+    errors: &mut Vec<syn::Error>,
+) -> Option<SecretSpec> {
+    let mut spec: Option<SecretSpec> = None;
+    let metas = attrs
+        .iter()
+        .filter(|attr| is_attr_with_path(attr, &["opaque_typedef"]))
+        .filter_map(|attr| attr.interpret_meta())
+        .flat_map(|meta| get_meta_content_by_path(meta, &["opaque_typedef"]))
+        .filter_map(|nested_meta| match nested_meta {
+            syn::NestedMeta::Meta(meta) => Some(meta),
+            syn::NestedMeta::Literal(..) => None,
+        });
+    for meta in metas {
+        let nested = match meta {
+            syn::Meta::Word(ref ident) if ident == "secret" => None,
+            syn::Meta::List(ref metalist) if metalist.ident == "secret" => {
+                Some(metalist.nested.clone())
+            }
+            syn::Meta::NameValue(ref nv) if nv.ident == "secret" => {
+                errors.push(syn::Error::new_spanned(
+                    nv,
+                    "`#[opaque_typedef(secret = ..)]` is not supported, \
+                     use `#[opaque_typedef(secret)]` instead",
+                ));
+                continue;
+            }
+            _ => continue,
+        };
+        let spec = spec.get_or_insert_with(|| SecretSpec {
+            allowed_derives: Vec::new(),
+        });
+        for nested_meta in nested.iter().flatten() {
+            match *nested_meta {
+                syn::NestedMeta::Meta(syn::Meta::List(ref metalist))
+                    if metalist.ident == "allow_derive" =>
+                {
+                    let metas = metalist
+                        .nested
+                        .iter()
+                        .filter_map(|nested_meta| match *nested_meta {
+                            syn::NestedMeta::Meta(ref meta) => Some(meta.clone()),
+                            syn::NestedMeta::Literal(..) => None,
+                        })
+                        .collect::<Vec<_>>();
+                    spec.allowed_derives.extend(
+                        Derive::from_metas(&metas, errors)
+                            .into_iter()
+                            .map(|(target, _)| target),
+                    );
+                }
+                ref nested_meta => errors.push(syn::Error::new_spanned(
+                    nested_meta,
+                    format!(
+                        "Unsupported `#[opaque_typedef(secret({}))]`, \
+                         expected `allow_derive(..)`",
+                        nested_meta.into_token_stream()
+                    ),
+                )),
+            }
+        }
+    }
+    // `Drop` impls cannot have extra bounds such as `T: Zeroize`.
+    if let (Some(_), Sizedness::Sized) = (&spec, sizedness) {
+        if let Some(param) = generics.type_params().next() {
+            errors.push(syn::Error::new_spanned(
+                &param.ident,
+                "`#[opaque_typedef(secret)]` is not supported for sized types with type \
+                 parameters, because the generated `Drop` impl cannot require `Zeroize` \
+                 for the inner type",
+            ));
+        }
+    }
+    spec
+}
+
+fn get_counterpart(
+    attrs: &[syn::Attribute],
+    sizedness: Sizedness,
+    errors: &mut Vec<syn::Error>,
+) -> Option<syn::Type> {
+    let (name, name_other) = match sizedness {
+        Sizedness::Sized => ("borrowed", "owned"),
+        Sizedness::Unsized => ("owned", "borrowed"),
+    };
+    let namevalues = attrs
+        .iter()
+        .filter(|attr| is_attr_with_path(attr, &["opaque_typedef"]))
+        .filter_map(|attr| attr.interpret_meta())
+        .flat_map(|meta| get_meta_content_by_path(meta, &["opaque_typedef"]))
+        .filter_map(|meta| match meta {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => Some(nv),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if let Some(nv) = namevalues.iter().find(|nv| nv.ident == name_other) {
+        let sizedness_str = match sizedness {
+            Sizedness::Sized => "sized",
+            Sizedness::Unsized => "unsized",
+        };
+        errors.push(syn::Error::new_spanned(
+            nv,
+            format!(
+                "`#[opaque_typedef({} = ..)]` is not supported for {} types, \
+                 use `#[opaque_typedef({} = ..)]` instead",
+                name_other, sizedness_str, name
+            ),
+        ));
+    }
+    let iter = namevalues.iter().filter(|nv| nv.ident == name);
+    let nv = match expect_singleton_iter(iter) {
+        SingletonIterResult::None => return None,
+        SingletonIterResult::Single(nv) => nv,
+        SingletonIterResult::Multiple(first, second) => {
+            errors.push(syn::Error::new_spanned(
+                second,
+                format!(
+                    "`#[opaque_typedef({} = ..)]` can be specified at most once for each type",
+                    name
+                ),
+            ));
+            first
+        }
+    };
+    let litstr = match nv.lit {
+        syn::Lit::Str(ref s) => s,
+        ref lit => {
+            errors.push(syn::Error::new_spanned(
+                lit,
+                format!(
+                    "String value is expected for `#[opaque_typedef({} = ..)]`, \
+                     but got `{}` (invalid type)",
+                    name,
+                    lit.into_token_stream()
+                ),
+            ));
+            return None;
+        }
+    };
+    parse_litstr::<syn::Type>(litstr, &format!("{} = ..", name), errors)
+}
+
+/// Returns custom `Display` spec if `#[opaque_typedef(display = ..)]` or
+/// `#[opaque_typedef(display_fmt = ..)]` is specified.
+fn get_display_spec(attrs: &[syn::Attribute], errors: &mut Vec<syn::Error>) -> Option<DisplaySpec> {
+    let namevalues = attrs
+        .iter()
+        .filter(|attr| is_attr_with_path(attr, &["opaque_typedef"]))
+        .filter_map(|attr| attr.interpret_meta())
+        .flat_map(|meta| get_meta_content_by_path(meta, &["opaque_typedef"]))
+        .filter_map(|meta| match meta {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => Some(nv),
+            _ => None,
+        })
+        .filter(|nv| nv.ident == "display" || nv.ident == "display_fmt")
+        .collect::<Vec<_>>();
+    let nv = match expect_singleton_iter(&namevalues) {
+        SingletonIterResult::None => return None,
+        SingletonIterResult::Single(nv) => nv,
+        SingletonIterResult::Multiple(first, second) => {
+            errors.push(syn::Error::new_spanned(
+                second,
+                "`#[opaque_typedef(display = ..)]` and `#[opaque_typedef(display_fmt = ..)]` \
+                 can be specified at most once in total for each type",
+            ));
+            first
+        }
+    };
+    let litstr = match nv.lit {
+        syn::Lit::Str(ref s) => s,
+        ref lit => {
+            errors.push(syn::Error::new_spanned(
+                lit,
+                format!(
+                    "String value is expected for `#[opaque_typedef({} = ..)]`, \
+                     but got `{}` (invalid type)",
+                    nv.ident,
+                    lit.into_token_stream()
+                ),
+            ));
+            return None;
+        }
+    };
+    if nv.ident == "display" {
+        return parse_litstr::<syn::Expr>(litstr, "display = ..", errors).map(DisplaySpec::Fn);
+    }
+    // The format should be simple enough to be parsed back.
+    match split_display_fmt(&litstr.value()) {
+        Ok((prefix, suffix, has_format_spec)) => Some(DisplaySpec::Fmt {
+            fmt: litstr.clone(),
+            prefix,
+            suffix,
+            has_format_spec,
+        }),
+        Err(reason) => {
+            errors.push(syn::Error::new_spanned(
+                litstr,
+                format!(
+                    "Invalid format string for `#[opaque_typedef(display_fmt = ..)]`: {}",
+                    reason
+                ),
+            ));
+            None
+        }
+    }
+}
+
+/// Splits the format string into the unescaped texts before and after the
+/// placeholder, and returns whether the placeholder has format spec.
+///
+/// The format string should have exactly one placeholder without argument
+/// names and positions, such as `{}` and `{:>8}`.
+fn split_display_fmt(fmt: &str) -> Result<(String, String, bool), &'static str> {
+    let mut prefix = String::new();
+    let mut suffix = String::new();
+    let mut has_placeholder = false;
+    let mut has_format_spec = false;
+    let mut chars = fmt.chars().peekable();
+    while let Some(c) = chars.next() {
+        let text = if has_placeholder {
+            &mut suffix
+        } else {
+            &mut prefix
+        };
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                if has_placeholder {
+                    return Err("expected exactly one placeholder, but got more");
+                }
+                let mut spec = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => spec.push(c),
+                        None => return Err("unmatched `{` found"),
+                    }
+                }
+                if !spec.is_empty() && !spec.starts_with(':') {
+                    return Err("named or positional arguments are not supported");
+                }
+                has_placeholder = true;
+                has_format_spec = !spec.is_empty();
+            }
+            '}' => return Err("unmatched `}` found"),
+            c => text.push(c),
+        }
+    }
+    if has_placeholder {
+        Ok((prefix, suffix, has_format_spec))
+    } else {
+        Err("expected exactly one placeholder, but got none")
+    }
+}
+
+/// Parses the string literal as the given syntax tree.
+///
+/// `attr` is the content of `#[opaque_typedef(..)]` used in the error message.
+fn parse_litstr<T: syn::parse::Parse>(
+    litstr: &syn::LitStr,
+    attr: &str,
+    errors: &mut Vec<syn::Error>,
+) -> Option<T> {
+    match litstr.parse::<T>() {
+        Ok(v) => Some(v),
+        Err(e) => {
+            errors.push(syn::Error::new_spanned(
+                litstr,
+                format!(
+                    "`#[opaque_typedef({})]` is specified \
+                     but failed to parse `{}`: {}",
+                    attr,
+                    litstr.value(),
+                    e
+                ),
+            ));
+            None
+        }
     }
 }
 
@@ -333,16 +1570,20 @@ fn get_cmp_spec(attrs: &[syn::Attribute]) -> CmpSpec {
 pub struct TypePropsBuilder<'a> {
     /// Outer type.
     ty_outer: Option<&'a syn::Ident>,
+    /// Visibility of the outer type.
+    vis: Option<&'a syn::Visibility>,
     /// `#[repr(..)]` spec of the outer type.
     repr_attr_outer: Option<syn::Meta>,
     /// Inner field.
     field_inner: Option<Field<'a>>,
+    /// Fields other than the inner field.
+    fields_extra: Option<Vec<ExtraField<'a>>>,
     /// Generics.
     generics: Option<&'a syn::Generics>,
     /// Sizedness of the inner type.
     inner_sizedness: Option<Sizedness>,
-    /// Derive target traits.
-    derives: Option<Vec<Derive>>,
+    /// Derive target traits and their spans.
+    derives: Option<Vec<(Derive, Span)>>,
     /// Deref spec.
     deref_spec: Option<DerefSpec>,
     /// Whether the mutable reference to the inner field is allowed.
@@ -351,6 +1592,14 @@ pub struct TypePropsBuilder<'a> {
     validation_spec: Option<ValidationSpec>,
     /// Cmp spec.
     cmp_spec: Option<CmpSpec>,
+    /// Ops spec.
+    ops_spec: Option<OpsSpec>,
+    /// Owned or borrowed counterpart type.
+    counterpart: Option<Option<syn::Type>>,
+    /// Secret mode spec.
+    secret_spec: Option<Option<SecretSpec>>,
+    /// Custom `Display` spec.
+    display_spec: Option<Option<DisplaySpec>>,
 }
 
 impl<'a> TypePropsBuilder<'a> {
@@ -360,48 +1609,173 @@ impl<'a> TypePropsBuilder<'a> {
     }
 
     /// Loads properties from the given input and sizedness.
-    pub fn load(&mut self, input: &'a DeriveInput, sizedness: Sizedness) {
+    ///
+    /// Errors are recorded to `errors`, and the property is left with a
+    /// fallback value if possible.
+    pub fn load(
+        &mut self,
+        input: &'a DeriveInput,
+        sizedness: Sizedness,
+        errors: &mut Vec<syn::Error>,
+    ) {
         self.ty_outer = Some(&input.ident);
-        self.repr_attr_outer = get_repr_meta(&input.attrs);
-        self.field_inner = Some(get_inner_field(&input.data));
+        self.vis = Some(&input.vis);
+        self.repr_attr_outer = get_repr_meta(&input.attrs, errors);
+        // `None` here is reported as an error.
+        if let Some((field_inner, fields_extra)) = get_fields(input, sizedness, errors) {
+            self.field_inner = Some(field_inner);
+            self.fields_extra = Some(fields_extra);
+        }
         self.generics = Some(&input.generics);
         self.inner_sizedness = Some(sizedness);
-        self.derives = Some(Derive::from_attrs(&input.attrs));
-        self.deref_spec = Some(get_deref_spec(&input.attrs));
-        self.is_mut_ref_allowed = Some(get_mut_ref_allowed(&input.attrs));
-        self.validation_spec = Some(get_validation_spec(&input.attrs));
-        self.cmp_spec = Some(get_cmp_spec(&input.attrs));
+        self.derives = Some(Derive::from_attrs(&input.attrs, errors));
+        self.deref_spec = Some(get_deref_spec(&input.attrs, errors));
+        let is_mut_ref_allowed = get_mut_ref_allowed(&input.attrs);
+        let float_not_nan = get_float_not_nan(&input.attrs, errors);
+        let validation_spec = get_validation_spec(
+            &input.attrs,
+            &input.ident,
+            &input.generics,
+            sizedness,
+            float_not_nan.as_ref(),
+            errors,
+        );
+        self.cmp_spec = Some(get_cmp_spec(&input.attrs, float_not_nan.as_ref(), errors));
+        let counterpart = get_counterpart(&input.attrs, sizedness, errors);
+        self.ops_spec = Some(get_ops_spec(
+            &input.attrs,
+            sizedness,
+            counterpart.as_ref(),
+            &validation_spec,
+            is_mut_ref_allowed,
+            errors,
+        ));
+        self.is_mut_ref_allowed = Some(is_mut_ref_allowed);
+        self.validation_spec = Some(validation_spec);
+        self.counterpart = Some(counterpart);
+        self.secret_spec = Some(get_secret_spec(
+            &input.attrs,
+            &input.generics,
+            sizedness,
+            errors,
+        ));
+        self.display_spec = Some(get_display_spec(&input.attrs, errors));
     }
 
     /// Builds a `TypeProps`.
-    pub fn build(self) -> TypeProps<'a> {
+    ///
+    /// Returns `None` if the type cannot be processed at all (for example,
+    /// the inner field is not found). The reason is recorded to `errors`.
+    pub fn build(self, errors: &mut Vec<syn::Error>) -> Option<TypeProps<'a>> {
         const MSG_SHOULD_LOAD: &str =
             "Should never happen: `TypePropsBuilder::load()` should be called at least once";
         let ty_outer = self.ty_outer.expect(MSG_SHOULD_LOAD);
-        let field_inner = self.field_inner.expect(MSG_SHOULD_LOAD);
+        let inner_sizedness = self.inner_sizedness.expect(MSG_SHOULD_LOAD);
+        check_repr_outer(
+            ty_outer,
+            inner_sizedness,
+            self.repr_attr_outer.as_ref(),
+            errors,
+        );
+        let field_inner = self.field_inner?;
+        let fields_extra = self.fields_extra.expect(MSG_SHOULD_LOAD);
         let generics = self.generics.expect(MSG_SHOULD_LOAD);
         let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
-        let inner_sizedness = self.inner_sizedness.expect(MSG_SHOULD_LOAD);
-        check_repr_outer(ty_outer, inner_sizedness, self.repr_attr_outer.as_ref());
-        let derives = self.derives.expect(MSG_SHOULD_LOAD);
+        let mut derives_with_spans = self.derives.expect(MSG_SHOULD_LOAD);
+        let cmp_spec = self.cmp_spec.expect(MSG_SHOULD_LOAD);
+        // The preset implies the consistent family of comparison traits.
+        // Presets with `cmp(constant_time)` are already reported as errors,
+        // so don't add the ordering traits which would be refused again.
+        let preset_span = match cmp_spec.constant_time {
+            Some(_) => None,
+            None => cmp_spec.preset_span(),
+        };
+        if let Some(span) = preset_span {
+            for &target in Derive::implied_by_cmp_preset() {
+                if !derives_with_spans.iter().any(|&(t, _)| t == target) {
+                    derives_with_spans.push((target, span));
+                }
+            }
+        }
+        let derives: Vec<_> = derives_with_spans
+            .iter()
+            .map(|&(target, _)| target)
+            .collect();
+        let mut derive_spans = HashMap::new();
+        for (target, span) in derives_with_spans {
+            derive_spans.entry(target).or_insert(span);
+        }
         let deref_spec = self.deref_spec.expect(MSG_SHOULD_LOAD);
         let is_mut_ref_allowed = self.is_mut_ref_allowed.expect(MSG_SHOULD_LOAD);
         let validation_spec = self.validation_spec.expect(MSG_SHOULD_LOAD);
-        let cmp_spec = self.cmp_spec.expect(MSG_SHOULD_LOAD);
+        if let Some(ref auto_error) = validation_spec.auto_error {
+            if !generics.params.is_empty() {
+                errors.push(syn::Error::new(
+                    auto_error.span,
+                    "`#[opaque_typedef(validation(error = \"auto\"))]` is not supported \
+                     for generic types",
+                ));
+            }
+        }
+        if let Some(ref hash) = cmp_spec.hash {
+            if !derives.contains(&Derive::Hash) {
+                errors.push(syn::Error::new_spanned(
+                    hash,
+                    "`#[opaque_typedef(cmp(hash = ..))]` is specified \
+                     but `#[opaque_typedef(derive(Hash))]` is not",
+                ));
+            }
+        }
+        let ops_spec = self.ops_spec.expect(MSG_SHOULD_LOAD);
+        let counterpart = self.counterpart.expect(MSG_SHOULD_LOAD);
+        let secret_spec = self.secret_spec.expect(MSG_SHOULD_LOAD);
+        if let (Some(_), Some(auto_error)) = (&secret_spec, &validation_spec.auto_error) {
+            if auto_error.keep_value {
+                errors.push(syn::Error::new(
+                    auto_error.span,
+                    "`#[opaque_typedef(validation(keep_value))]` cannot be used with \
+                     `#[opaque_typedef(secret)]`, because the error reveals the rejected value",
+                ));
+            }
+        }
+        let display_spec = self.display_spec.expect(MSG_SHOULD_LOAD);
+        if let Some(ref spec) = display_spec {
+            if !derives.contains(&Derive::Display) {
+                let (attr, tokens) = match *spec {
+                    DisplaySpec::Fn(ref expr) => ("display", expr.into_token_stream()),
+                    DisplaySpec::Fmt { ref fmt, .. } => ("display_fmt", fmt.into_token_stream()),
+                };
+                errors.push(syn::Error::new_spanned(
+                    tokens,
+                    format!(
+                        "`#[opaque_typedef({} = ..)]` is specified \
+                         but `#[opaque_typedef(derive(Display))]` is not",
+                        attr
+                    ),
+                ));
+            }
+        }
 
-        TypeProps {
+        Some(TypeProps {
             ty_outer,
+            vis: self.vis.expect(MSG_SHOULD_LOAD),
             field_inner,
+            fields_extra,
             generics,
             impl_generics,
             type_generics,
             where_clause,
             inner_sizedness,
             derives,
+            derive_spans,
             deref_spec,
             is_mut_ref_allowed,
             validation_spec,
             cmp_spec,
-        }
+            ops_spec,
+            counterpart,
+            secret_spec,
+            display_spec,
+        })
     }
 }
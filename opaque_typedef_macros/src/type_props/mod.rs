@@ -1,11 +1,20 @@
 //! Type properties.
 
-use proc_macro2::TokenStream;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use proc_macro2::{Span, TokenStream};
 use quote::ToTokens;
 use syn;
+use syn::spanned::Spanned;
 use syn::DeriveInput;
 
-use derives::Derive;
+use derives::{
+    gen_impl_check_normalized, gen_impl_check_validator, gen_impl_from_inner_cow,
+    gen_impls_auto_error, gen_impls_borrow_pair, gen_impls_secret, Derive,
+};
+use paths;
+use utils::extend_generics;
 
 use self::builder::TypePropsBuilder;
 
@@ -38,6 +47,13 @@ impl<'a> Field<'a> {
         }
     }
 
+    /// Returns the field.
+    pub fn raw(&self) -> &'a syn::Field {
+        match *self {
+            Field::Named(field) | Field::Unnamed(field, _) => field,
+        }
+    }
+
     /// Returns the name of the field.
     pub fn name(&self) -> TokenStream {
         match *self {
@@ -53,6 +69,17 @@ impl<'a> Field<'a> {
     }
 }
 
+/// A field other than the inner field.
+#[derive(Clone)]
+pub struct ExtraField<'a> {
+    /// Field.
+    pub field: Field<'a>,
+    /// Expression to initialize the field.
+    pub init: TokenStream,
+    /// Whether the field is `PhantomData` without explicit initializer.
+    pub is_phantom: bool,
+}
+
 #[derive(Default, Clone)]
 pub struct DerefSpec {
     /// Deref target type.
@@ -71,31 +98,96 @@ pub struct DerefSpec {
 pub struct ValidationSpec {
     /// Validator.
     pub fn_validator: Option<syn::Expr>,
+    /// Checker, which should have `&Inner -> Result<(), Error>` type.
+    ///
+    /// If this is given by `#[opaque_typedef(validation(check = ..))]`,
+    /// the validator is generated from it.
+    pub fn_check: Option<syn::Expr>,
+    /// Validator value implementing `opaque_typedef::validator::Validator`.
+    ///
+    /// If this is given by `#[opaque_typedef(validation(with = ..))]`, the
+    /// checker is generated from it.
+    pub validator_with: Option<syn::Expr>,
+    /// Normalizer, which is applied before the validator.
+    ///
+    /// The function should have `Inner -> Inner` type for sized types, and
+    /// `&Inner -> Cow<Inner>` type for unsized types.
+    pub fn_normalize: Option<syn::Expr>,
+    /// Error constructor for values which are not normalized, which should
+    /// have `&Inner -> Error` type.
+    ///
+    /// This is used by unsized types, which cannot normalize values given
+    /// by reference.
+    pub fn_not_normalized: Option<syn::Expr>,
     /// Validation error type.
     pub ty_error: Option<syn::Type>,
     /// Validation error message.
     pub error_msg: Option<String>,
+    /// Generated error type spec.
+    pub auto_error: Option<AutoErrorSpec>,
+    /// Whether the validator returns `opaque_typedef::Rejected<Inner, Error>`
+    /// as an error.
+    pub rejected: bool,
+}
+
+/// Generated validation error type spec
+/// (`#[opaque_typedef(validation(error = "auto"))]`).
+///
+/// The validator of the type is generated from the predicate.
+#[derive(Clone)]
+pub struct AutoErrorSpec {
+    /// Span of the attribute.
+    pub span: Span,
+    /// Name of the generated error type.
+    pub ty_error: syn::Ident,
+    /// Predicate, which should have `&Inner -> bool` type.
+    pub fn_predicate: syn::Expr,
+    /// Error message.
+    pub message: String,
+    /// Whether the error holds the rejected value.
+    pub keep_value: bool,
 }
 
 impl ValidationSpec {
     pub fn tokens_try_validated<T: ToTokens>(&self, inner: T) -> TokenStream {
-        match self.fn_validator {
-            Some(ref validator) => quote!(#validator(#inner)?),
+        match self.tokens_validation_result(&inner) {
+            Some(result) => quote!(#result?),
             None => inner.into_token_stream(),
         }
     }
 
     pub fn tokens_validated<T: ToTokens>(&self, inner: T) -> TokenStream {
-        let validation_result = match self.fn_validator {
-            Some(ref validator) => quote!(#validator(#inner)),
-            None => return inner.into_token_stream(),
-        };
+        match self.tokens_validation_result(&inner) {
+            Some(result) => self.tokens_unwrapped(result),
+            None => inner.into_token_stream(),
+        }
+    }
+
+    /// Returns the value of the validation result, which panics with the
+    /// error message if the result is an error.
+    pub fn tokens_unwrapped<T: ToTokens>(&self, result: T) -> TokenStream {
         match self.error_msg {
-            Some(ref msg) => quote!(#validation_result.expect(#msg)),
-            None => quote!(#validation_result.unwrap()),
+            Some(ref msg) => quote!(#result.expect(#msg)),
+            None => quote!(#result.unwrap()),
+        }
+    }
+
+    /// Returns the validation result with `Self::Error` as an error type.
+    fn tokens_validation_result<T: ToTokens>(&self, inner: T) -> Option<TokenStream> {
+        let validator = self.fn_validator.as_ref()?;
+        if self.rejected {
+            Some(quote!(#validator(#inner).map_err(::opaque_typedef::Rejected::into_error)))
+        } else {
+            Some(quote!(#validator(#inner)))
         }
     }
 
+    /// Returns whether the value can be validated by reference, without
+    /// cloning.
+    pub fn is_borrowed_check_available(&self) -> bool {
+        self.fn_normalize.is_none() && (self.fn_validator.is_none() || self.fn_check.is_some())
+    }
+
     pub fn tokens_ty_error(&self) -> TokenStream {
         match self.ty_error {
             Some(ref ty) => ty.into_token_stream(),
@@ -112,10 +204,46 @@ pub struct CmpSpec {
     pub partial_ord: Option<syn::Expr>,
     /// `Ord` comparator.
     pub ord: Option<syn::Expr>,
+    /// `Hash` function.
+    pub hash: Option<syn::Expr>,
+    /// Whether the default `Hash` function is allowed with the custom
+    /// `PartialEq` comparator.
+    pub allow_default_hash: bool,
+    /// Key function.
+    ///
+    /// If specified, all comparators and the hash function compare the keys.
+    pub key: Option<syn::Expr>,
+    /// `ascii_case_insensitive` preset.
+    ///
+    /// If specified, all comparators and the hash function ignore ASCII case.
+    pub ascii_case_insensitive: Option<syn::Ident>,
+    /// `float(not_nan)` preset.
+    ///
+    /// If specified, all comparators and the hash function are total for
+    /// non-NaN floating point numbers.
+    pub float_not_nan: Option<syn::Ident>,
+    /// `constant_time` equality comparator.
+    ///
+    /// If specified, the `PartialEq` comparator takes time independent of
+    /// the contents, and ordering comparators are unavailable.
+    pub constant_time: Option<syn::Ident>,
 }
 
 impl CmpSpec {
     pub fn partial_eq(&self) -> TokenStream {
+        if self.constant_time.is_some() {
+            return quote!(::opaque_typedef::cmp::constant_time::eq);
+        }
+        if self.float_not_nan.is_some() {
+            return quote!(::opaque_typedef::cmp::not_nan::eq);
+        }
+        if self.ascii_case_insensitive.is_some() {
+            return quote!(::opaque_typedef::cmp::ascii_case_insensitive::eq);
+        }
+        if let Some(ref key) = self.key {
+            let core = paths::core();
+            return quote!((|a, b| #core::cmp::PartialEq::eq(&(#key)(a), &(#key)(b))));
+        }
         match self.partial_eq {
             Some(ref v) => v.into_token_stream(),
             None => quote!(PartialEq::eq),
@@ -123,6 +251,16 @@ impl CmpSpec {
     }
 
     pub fn partial_ord(&self) -> TokenStream {
+        if self.float_not_nan.is_some() {
+            return quote!(::opaque_typedef::cmp::not_nan::partial_cmp);
+        }
+        if self.ascii_case_insensitive.is_some() {
+            return quote!(::opaque_typedef::cmp::ascii_case_insensitive::partial_cmp);
+        }
+        if let Some(ref key) = self.key {
+            let core = paths::core();
+            return quote!((|a, b| #core::cmp::PartialOrd::partial_cmp(&(#key)(a), &(#key)(b))));
+        }
         match self.partial_ord {
             Some(ref v) => v.into_token_stream(),
             None => quote!(PartialOrd::partial_cmp),
@@ -130,11 +268,152 @@ impl CmpSpec {
     }
 
     pub fn ord(&self) -> TokenStream {
+        if self.float_not_nan.is_some() {
+            return quote!(::opaque_typedef::cmp::not_nan::cmp);
+        }
+        if self.ascii_case_insensitive.is_some() {
+            return quote!(::opaque_typedef::cmp::ascii_case_insensitive::cmp);
+        }
+        if let Some(ref key) = self.key {
+            let core = paths::core();
+            return quote!((|a, b| #core::cmp::Ord::cmp(&(#key)(a), &(#key)(b))));
+        }
         match self.ord {
             Some(ref v) => v.into_token_stream(),
             None => quote!(Ord::cmp),
         }
     }
+
+    pub fn hash(&self) -> TokenStream {
+        let core = paths::core();
+        if self.float_not_nan.is_some() {
+            return quote!(::opaque_typedef::cmp::not_nan::hash);
+        }
+        if self.ascii_case_insensitive.is_some() {
+            return quote!(::opaque_typedef::cmp::ascii_case_insensitive::hash);
+        }
+        if let Some(ref key) = self.key {
+            return quote!((|v, state| #core::hash::Hash::hash(&(#key)(v), state)));
+        }
+        match self.hash {
+            Some(ref v) => v.into_token_stream(),
+            None => quote!(#core::hash::Hash::hash),
+        }
+    }
+
+    /// Returns the span of the attribute which determines all the
+    /// comparators and the hash function, if exists.
+    ///
+    /// Such attributes are `cmp(key = ..)`, `cmp(ascii_case_insensitive)`,
+    /// and `float(not_nan)`.
+    pub fn preset_span(&self) -> Option<Span> {
+        if let Some(ref ident) = self.float_not_nan {
+            return Some(ident.span());
+        }
+        if let Some(ref ident) = self.ascii_case_insensitive {
+            return Some(ident.span());
+        }
+        self.key.as_ref().map(|key| key.span())
+    }
+
+    /// Returns whether the comparators and the hash function use the traits
+    /// of the inner type.
+    pub fn uses_inner_traits(&self) -> bool {
+        self.preset_span().is_none()
+    }
+}
+
+/// Output of operators for unsized types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumString)]
+pub enum UnsizedOpOutput {
+    /// The result of the operation on the owned inner value.
+    #[strum(serialize = "inner")]
+    Inner,
+    /// The owned counterpart type (specified by `#[opaque_typedef(owned = ..)]`).
+    #[strum(serialize = "owned")]
+    Owned,
+}
+
+/// Strategy to implement `*Assign` operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumString)]
+pub enum AssignOpsStrategy {
+    /// Implement `a op= b` as `a = Outer::from_inner(a.inner.clone() op b)`.
+    ///
+    /// The result is validated.
+    #[strum(serialize = "via_binop")]
+    ViaBinop,
+    /// Implement `a op= b` as `a.inner op= b`.
+    ///
+    /// The result is not validated.
+    #[strum(serialize = "in_place")]
+    InPlace,
+}
+
+/// Policy for operator results which fail validation.
+#[derive(Clone)]
+pub enum OnInvalidOpResult {
+    /// Panic (by `from_inner()`).
+    Panic,
+    /// Implement `opaque_typedef::ops::Try*` traits instead of operator
+    /// traits, and return the validation error.
+    Checked,
+    /// Operate on the inner values by saturating arithmetic, and apply the
+    /// given clamp function to the result before validation.
+    Saturate(syn::Expr),
+    /// Operate on the inner values by wrapping arithmetic, and apply the
+    /// given wrap function to the result before validation.
+    Wrap(syn::Expr),
+}
+
+#[derive(Clone)]
+pub struct OpsSpec {
+    /// Output of operators for unsized types.
+    pub unsized_output: UnsizedOpOutput,
+    /// Policy for operator results which fail validation.
+    pub on_invalid: OnInvalidOpResult,
+    /// Strategy to implement `*Assign` operators.
+    pub assign_ops: AssignOpsStrategy,
+    /// Functions to compute the owned inner result of operators for unsized
+    /// types, with the method names of the operators (such as `bitxor`).
+    pub unsized_fns: Vec<(&'static str, syn::Expr)>,
+}
+
+impl OpsSpec {
+    /// Returns the function specified by `#[opaque_typedef(ops(method = ..))]`.
+    pub fn unsized_fn(&self, method: &str) -> Option<&syn::Expr> {
+        self.unsized_fns
+            .iter()
+            .find(|(name, _)| *name == method)
+            .map(|(_, expr)| expr)
+    }
+}
+
+/// Custom `Display` spec.
+#[derive(Clone)]
+pub enum DisplaySpec {
+    /// `#[opaque_typedef(display = "path::to::fn")]`.
+    ///
+    /// The function should have `(&Inner, &mut Formatter) -> fmt::Result` type.
+    Fn(syn::Expr),
+    /// `#[opaque_typedef(display_fmt = "prefix{}suffix")]`.
+    Fmt {
+        /// Format string.
+        fmt: syn::LitStr,
+        /// Text before the placeholder (unescaped).
+        prefix: String,
+        /// Text after the placeholder (unescaped).
+        suffix: String,
+        /// Whether the placeholder has format spec (such as `{:>8}`).
+        has_format_spec: bool,
+    },
+}
+
+/// Secret mode spec (`#[opaque_typedef(secret)]`).
+#[derive(Clone)]
+pub struct SecretSpec {
+    /// Revealing derive targets which are explicitly forced by
+    /// `#[opaque_typedef(secret(allow_derive(..)))]`.
+    pub allowed_derives: Vec<Derive>,
 }
 
 /// Properties of a type with `#[derive(OpaqueTypedef*)]`.
@@ -142,8 +421,12 @@ impl CmpSpec {
 pub struct TypeProps<'a> {
     /// Outer type.
     pub ty_outer: &'a syn::Ident,
+    /// Visibility of the outer type.
+    pub vis: &'a syn::Visibility,
     /// Inner field.
     pub field_inner: Field<'a>,
+    /// Fields other than the inner field.
+    pub fields_extra: Vec<ExtraField<'a>>,
     /// Generics.
     pub generics: &'a syn::Generics,
     /// Impl generics (cache).
@@ -156,6 +439,8 @@ pub struct TypeProps<'a> {
     pub inner_sizedness: Sizedness,
     /// Derive target traits.
     pub derives: Vec<Derive>,
+    /// Spans of derive targets in `#[opaque_typedef(derive(..))]`.
+    pub derive_spans: HashMap<Derive, Span>,
     /// Deref spec.
     pub deref_spec: DerefSpec,
     /// Whether the mutable reference to the inner field is allowed.
@@ -164,41 +449,221 @@ pub struct TypeProps<'a> {
     pub validation_spec: ValidationSpec,
     /// Cmp spec.
     pub cmp_spec: CmpSpec,
+    /// Ops spec.
+    pub ops_spec: OpsSpec,
+    /// Owned or borrowed counterpart type.
+    ///
+    /// This is the owned type for unsized types, and the borrowed type for
+    /// sized types.
+    pub counterpart: Option<syn::Type>,
+    /// Secret mode spec.
+    pub secret_spec: Option<SecretSpec>,
+    /// Custom `Display` spec.
+    pub display_spec: Option<DisplaySpec>,
 }
 
 impl<'a> TypeProps<'a> {
     /// Load properties from the given input and sizedness.
-    pub fn load(input: &'a DeriveInput, sizedness: Sizedness) -> Self {
+    ///
+    /// Errors are recorded to `errors`.
+    pub fn load(
+        input: &'a DeriveInput,
+        sizedness: Sizedness,
+        errors: &mut Vec<syn::Error>,
+    ) -> Option<Self> {
         let mut builder = TypePropsBuilder::new();
-        builder.load(input, sizedness);
-        builder.build()
+        builder.load(input, sizedness, errors);
+        builder.build(errors)
     }
 
     /// Generates implementations for the target type.
-    pub fn gen_impls(&self) -> TokenStream {
+    ///
+    /// Errors are recorded to `errors`, and impls for them are skipped.
+    pub fn gen_impls(&self, errors: &mut Vec<syn::Error>) -> TokenStream {
         let basic_impl = self.impl_basic_helper_trait();
+        let pair_impls = gen_impls_borrow_pair(self).unwrap_or_else(|e| {
+            errors.push(e);
+            quote!()
+        });
+        let secret_impls = gen_impls_secret(self).unwrap_or_else(|e| {
+            errors.push(e);
+            quote!()
+        });
+        let auto_error_impls = gen_impls_auto_error(self);
+        let check_validator_impl = gen_impl_check_validator(self);
+        let check_normalized_impl = gen_impl_check_normalized(self);
+        let from_inner_cow_impl = gen_impl_from_inner_cow(self).unwrap_or_else(|e| {
+            errors.push(e);
+            quote!()
+        });
+        let phantom_assertion = self.gen_phantom_data_assertion();
         let derive_impls = self
             .derives
             .iter()
-            .map(|derive| derive.impl_auto_derive(self))
+            .filter_map(|derive| match derive.impl_auto_derive(self) {
+                Ok(tokens) => Some(tokens),
+                Err(e) => {
+                    errors.push(e);
+                    None
+                }
+            })
             .collect::<Vec<_>>();
         quote! {
             #basic_impl
+            #pair_impls
+            #secret_impls
+            #auto_error_impls
+            #check_validator_impl
+            #check_normalized_impl
+            #from_inner_cow_impl
+            #phantom_assertion
             #(#derive_impls)*
         }
     }
 
+    /// Generates the assertion that the fields detected as `PhantomData` are
+    /// really `core::marker::PhantomData`.
+    ///
+    /// Unsized outer values are casted from the inner values, so the extra
+    /// fields must be zero-sized.
+    fn gen_phantom_data_assertion(&self) -> TokenStream {
+        let core = paths::core();
+        let assertions = self
+            .fields_extra
+            .iter()
+            .filter(|extra| extra.is_phantom)
+            .map(|extra| {
+                let ty = extra.field.ty();
+                quote_spanned!(ty.span()=> let _: #ty = #core::marker::PhantomData;)
+            })
+            .collect::<Vec<_>>();
+        if assertions.is_empty() {
+            return quote!();
+        }
+        let ty_outer = self.ty_outer;
+        let impl_generics = &self.impl_generics;
+        let type_generics = &self.type_generics;
+        let where_clause = &self.where_clause;
+        quote! {
+            impl #impl_generics #ty_outer #type_generics
+            #where_clause
+            {
+                #[doc(hidden)]
+                #[allow(dead_code)]
+                fn __opaque_typedef_assert_phantom_data() {
+                    #(#assertions)*
+                }
+            }
+        }
+    }
+
+    /// Returns `name: init` pairs to initialize fields other than the inner field.
+    fn tokens_fields_extra_init(&self) -> Vec<TokenStream> {
+        self.fields_extra
+            .iter()
+            .map(|extra| {
+                let name = extra.field.name();
+                let init = &extra.init;
+                quote!(#name: #init)
+            })
+            .collect()
+    }
+
     /// Generates impl for `OpaqueTypedef*` trait.
     pub fn impl_basic_helper_trait(&self) -> TokenStream {
         let ty_outer = self.ty_outer;
         let ty_inner = self.field_inner.ty();
         let name_inner = self.field_inner.name();
-        let impl_generics = &self.impl_generics;
+        let generics = self.generics_with_helper_trait_preds();
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
         let type_generics = &self.type_generics;
-        let where_clause = &self.where_clause;
         let ty_error = self.validation_spec.tokens_ty_error();
-        let inner_try_validated = self.validation_spec.tokens_try_validated(quote!(__inner));
-        let inner_validated = self.validation_spec.tokens_validated(quote!(__inner));
+        // Unsized types are normalized only by `Outer::from_inner_cow`.
+        let inner_normalized = match (self.inner_sizedness, &self.validation_spec.fn_normalize) {
+            (Sizedness::Sized, Some(fn_normalize)) => quote!(#fn_normalize(__inner)),
+            _ => quote!(__inner),
+        };
+        let inner_try_validated = self.validation_spec.tokens_try_validated(&inner_normalized);
+        let inner_validated = self.validation_spec.tokens_validated(&inner_normalized);
+        // Unsized types reject values which are not normalized.
+        let (try_check_normalized, check_normalized) =
+            match (self.inner_sizedness, &self.validation_spec.fn_normalize) {
+                (Sizedness::Unsized, Some(_)) => {
+                    let result = quote!(Self::__opaque_typedef_check_normalized(__inner));
+                    let unwrapped = self.validation_spec.tokens_unwrapped(&result);
+                    (quote!(#result?;), quote!(#unwrapped;))
+                }
+                _ => (quote!(), quote!()),
+            };
+        let fields_extra = &self.tokens_fields_extra_init();
+        let core = paths::core();
+        let into_inner = if self.secret_spec.is_some() {
+            // The type implements `Drop`, so the inner field cannot be moved
+            // out directly.
+            let drop_extra = self
+                .fields_extra
+                .iter()
+                .map(|extra| {
+                    let name = extra.field.name();
+                    quote!(#core::ptr::drop_in_place(&mut __this.#name);)
+                })
+                .collect::<Vec<_>>();
+            quote! {
+                let mut __this = #core::mem::ManuallyDrop::new(self);
+                unsafe {
+                    #(#drop_extra)*
+                    #core::ptr::read(&__this.#name_inner)
+                }
+            }
+        } else {
+            quote!(self.#name_inner)
+        };
+        // Overridden only if the rejected value can be taken back without
+        // cloning. With a normalizer, the rejected value is normalized, so
+        // the default implementation is used to hand the given value back.
+        let try_from_inner_or_return = match (
+            &self.validation_spec.fn_validator,
+            self.validation_spec.rejected,
+            &self.validation_spec.fn_normalize,
+        ) {
+            (None, _, _) => quote! {
+                fn try_from_inner_or_return(
+                    __inner: Self::Inner
+                ) -> #core::result::Result<Self, (Self::Inner, Self::Error)> {
+                    Ok(Self { #name_inner: #inner_normalized, #(#fields_extra),* })
+                }
+            },
+            (Some(validator), true, None) => quote! {
+                fn try_from_inner_or_return(
+                    __inner: Self::Inner
+                ) -> #core::result::Result<Self, (Self::Inner, Self::Error)> {
+                    match #validator(__inner) {
+                        Ok(__inner) => Ok(Self { #name_inner: __inner, #(#fields_extra),* }),
+                        Err(__rejected) => Err(::opaque_typedef::Rejected::into_parts(__rejected)),
+                    }
+                }
+            },
+            (Some(_), _, _) => quote!(),
+        };
+        // Overridden only if the value can be checked without cloning.
+        let validate = match (
+            &self.validation_spec.fn_validator,
+            &self.validation_spec.fn_check,
+        ) {
+            (None, _) => quote! {
+                fn validate(_: &Self::Inner) -> #core::result::Result<(), Self::Error> {
+                    Ok(())
+                }
+            },
+            (Some(_), Some(fn_check)) if self.validation_spec.is_borrowed_check_available() => {
+                quote! {
+                    fn validate(__inner: &Self::Inner) -> #core::result::Result<(), Self::Error> {
+                        #fn_check(__inner)
+                    }
+                }
+            }
+            (Some(_), _) => quote!(),
+        };
         match self.inner_sizedness {
             Sizedness::Sized => {
                 quote! {
@@ -209,16 +674,18 @@ impl<'a> TypeProps<'a> {
                         type Error = #ty_error;
 
                         unsafe fn from_inner_unchecked(__inner: Self::Inner) -> Self {
-                            Self { #name_inner: __inner }
+                            Self { #name_inner: __inner, #(#fields_extra),* }
                         }
-                        fn try_from_inner(__inner: Self::Inner) -> std::result::Result<Self, Self::Error> {
-                            Ok(Self { #name_inner: #inner_try_validated })
+                        fn try_from_inner(__inner: Self::Inner) -> #core::result::Result<Self, Self::Error> {
+                            Ok(Self { #name_inner: #inner_try_validated, #(#fields_extra),* })
                         }
+                        #try_from_inner_or_return
+                        #validate
                         fn from_inner(__inner: Self::Inner) -> Self {
-                            Self { #name_inner: #inner_validated }
+                            Self { #name_inner: #inner_validated, #(#fields_extra),* }
                         }
                         fn into_inner(self) -> Self::Inner {
-                            self.#name_inner
+                            #into_inner
                         }
                         fn as_inner(&self) -> &Self::Inner {
                             &self.#name_inner
@@ -248,19 +715,23 @@ impl<'a> TypeProps<'a> {
                             // <https://rust-lang-nursery.github.io/rust-clippy/v0.0.194/index.html#derive_hash_xor_eq>.
                             &mut *(__inner as *mut Self::Inner as *mut Self)
                         }
-                        fn try_from_inner(__inner: &Self::Inner) -> std::result::Result<&Self, Self::Error> {
+                        fn try_from_inner(__inner: &Self::Inner) -> #core::result::Result<&Self, Self::Error> {
+                            #try_check_normalized
                             let __inner = #inner_try_validated;
                             Ok(unsafe { <Self as ::opaque_typedef::OpaqueTypedefUnsized>::from_inner_unchecked(__inner) })
                         }
                         fn from_inner(__inner: &Self::Inner) -> &Self {
+                            #check_normalized
                             let __inner = #inner_validated;
                             unsafe { <Self as ::opaque_typedef::OpaqueTypedefUnsized>::from_inner_unchecked(__inner) }
                         }
-                        fn try_from_inner_mut(__inner: &mut Self::Inner) -> std::result::Result<&mut Self, Self::Error> {
+                        fn try_from_inner_mut(__inner: &mut Self::Inner) -> #core::result::Result<&mut Self, Self::Error> {
+                            #try_check_normalized
                             let __inner = #inner_try_validated;
                             Ok(unsafe { <Self as ::opaque_typedef::OpaqueTypedefUnsized>::from_inner_unchecked_mut(__inner) })
                         }
                         fn from_inner_mut(__inner: &mut Self::Inner) -> &mut Self {
+                            #check_normalized
                             let __inner = #inner_validated;
                             unsafe { <Self as ::opaque_typedef::OpaqueTypedefUnsized>::from_inner_unchecked_mut(__inner) }
                         }
@@ -291,17 +762,27 @@ impl<'a> TypeProps<'a> {
             Sizedness::Sized,
             "opaque_typedef internal error: Caller should ensure the (inner) type is sized"
         );
-        let ty_outer = self.ty_outer;
-        let type_generics = &self.type_generics;
-        let helper_trait = self.helper_trait();
-        quote!(<#ty_outer #type_generics as #helper_trait>::into_inner(#expr))
+        // The inner value cannot be moved out of types with `Drop` impl.
+        if self.secret_spec.is_some() {
+            let ty_outer = self.ty_outer;
+            let type_generics = &self.type_generics;
+            let helper_trait = self.helper_trait();
+            return quote!(<#ty_outer #type_generics as #helper_trait>::into_inner(#expr));
+        }
+        let name_inner = self.field_inner.name();
+        quote!((#expr).#name_inner)
     }
 
     pub fn tokens_outer_expr_as_inner<T: ToTokens>(&self, expr: T) -> TokenStream {
-        let ty_outer = self.ty_outer;
-        let type_generics = &self.type_generics;
-        let helper_trait = self.helper_trait();
-        quote!(<#ty_outer #type_generics as #helper_trait>::as_inner(#expr))
+        let name_inner = self.field_inner.name();
+        quote!((&(#expr).#name_inner))
+    }
+
+    pub fn tokens_outer_expr_as_deref_target<T: ToTokens>(&self, expr: T) -> TokenStream {
+        let expr_as_inner = self.tokens_outer_expr_as_inner(expr);
+        let ty_deref_target = self.tokens_ty_deref_target();
+        let fn_name_deref = self.tokens_fn_deref();
+        quote!((#fn_name_deref(#expr_as_inner) as &#ty_deref_target))
     }
 
     pub fn tokens_outer_expr_as_inner_mut<T: ToTokens>(&self, expr: T) -> TokenStream {
@@ -315,14 +796,8 @@ impl<'a> TypeProps<'a> {
     }
 
     pub fn tokens_outer_expr_as_inner_mut_nocheck<T: ToTokens>(&self, expr: T) -> TokenStream {
-        let ty_outer = self.ty_outer;
-        let type_generics = &self.type_generics;
-        let helper_trait = self.helper_trait();
-        quote! {
-            unsafe {
-                <#ty_outer #type_generics as #helper_trait>::as_inner_mut(#expr)
-            }
-        }
+        let name_inner = self.field_inner.name();
+        quote!((&mut (#expr).#name_inner))
     }
 
     pub fn tokens_ty_deref_target(&self) -> TokenStream {
@@ -346,7 +821,42 @@ impl<'a> TypeProps<'a> {
             .map_or_else(|| quote!(), |name| name.into_token_stream())
     }
 
+    /// Returns the span of the derive target.
+    pub fn derive_span(&self, target: Derive) -> Span {
+        self.derive_spans
+            .get(&target)
+            .cloned()
+            .unwrap_or_else(Span::call_site)
+    }
+
     pub fn has_type_params(&self) -> bool {
         self.generics.type_params().next().is_some()
     }
+
+    /// Returns where predicates required to use the helper trait.
+    ///
+    /// The helper traits require `Error: Debug`, and the validation error
+    /// type can use type parameters of the outer type (such as `EmptyVec<T>`
+    /// for `NonEmptyVec<T>`).
+    /// The bound is added only to the impls using the helper trait or the
+    /// error type, so that other impls are available for any type parameters.
+    pub fn helper_trait_preds(&self) -> Vec<syn::WherePredicate> {
+        match self.validation_spec.ty_error {
+            Some(ref ty_error) if self.has_type_params() => {
+                let pred = syn::parse_str::<syn::WherePredicate>(&format!(
+                    "{}: {}::fmt::Debug",
+                    ty_error.into_token_stream(),
+                    paths::core()
+                ))
+                .expect("Failed to generate `WherePredicate`");
+                vec![pred]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns generics with the bounds required to use the helper trait.
+    pub fn generics_with_helper_trait_preds(&self) -> Cow<'a, syn::Generics> {
+        extend_generics(Cow::Borrowed(self.generics), 0, &self.helper_trait_preds()).0
+    }
 }
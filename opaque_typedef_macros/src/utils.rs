@@ -17,19 +17,6 @@ pub enum SingletonIterResult<T> {
     Multiple(T, T),
 }
 
-impl<T> SingletonIterResult<T> {
-    /// Returns `Some(None)` for no items, `Some(Some(T))` for a single item,
-    /// `None` for multiple items.
-    #[allow(clippy::option_option)]
-    pub fn at_most_one(self) -> Option<Option<T>> {
-        match self {
-            SingletonIterResult::None => Some(None),
-            SingletonIterResult::Single(v) => Some(Some(v)),
-            SingletonIterResult::Multiple(..) => None,
-        }
-    }
-}
-
 /// Checks whether the iterator has at most one item, and returns the items.
 pub fn expect_singleton_iter<I, T>(iter: I) -> SingletonIterResult<T>
 where
@@ -0,0 +1,29 @@
+//! A string type compared case-insensitively for ASCII characters.
+
+use std::hash::{Hash, Hasher};
+
+/// A string type compared case-insensitively for ASCII characters.
+#[derive(Default, Debug, Clone, Eq, OpaqueTypedef)]
+#[opaque_typedef(derive(
+    AsRef(Deref),
+    Deref,
+    Display,
+    FromInner,
+    Hash,
+    IntoInner,
+    PartialEqSelf
+))]
+#[opaque_typedef(cmp(
+    partial_eq = "(|a: &String, b: &String| a.eq_ignore_ascii_case(b))",
+    hash = "hash_ascii_lowercase"
+))]
+pub struct AsciiCaselessString(String);
+
+/// Hashes the string consistently with `eq_ignore_ascii_case`.
+fn hash_ascii_lowercase<H: Hasher>(s: &str, state: &mut H) {
+    for b in s.bytes() {
+        b.to_ascii_lowercase().hash(state);
+    }
+    // Same as `str::hash`.
+    0xffu8.hash(state);
+}
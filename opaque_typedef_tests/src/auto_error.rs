@@ -0,0 +1,28 @@
+//! Types with generated validation error types.
+
+/// An even number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, OpaqueTypedef)]
+#[opaque_typedef(derive(Display, FromStr, IntoInner))]
+#[opaque_typedef(validation(
+    validator = "is_even",
+    error = "auto",
+    message = "the number is odd",
+    keep_value
+))]
+pub struct Even(i32);
+
+fn is_even(v: &i32) -> bool {
+    v % 2 == 0
+}
+
+/// A string slice without whitespaces.
+#[derive(Debug, PartialEq, Eq, OpaqueTypedefUnsized)]
+#[repr(C)]
+#[opaque_typedef(derive(AsRef(Inner)))]
+#[opaque_typedef(allow_mut_ref)]
+#[opaque_typedef(validation(validator = "is_word", error = "auto"))]
+pub struct Word(str);
+
+fn is_word(v: &str) -> bool {
+    !v.contains(char::is_whitespace)
+}
@@ -0,0 +1,27 @@
+//! String types compared by their lengths.
+
+/// A string slice compared by its length.
+#[derive(Debug, OpaqueTypedefUnsized)]
+#[repr(C)]
+#[opaque_typedef(derive(
+    AsRef(Deref),
+    Deref,
+    FromInner,
+    PartialEq(Inner, InnerRev, InnerCow, InnerCowRev),
+    PartialOrd(Inner, InnerRev)
+))]
+#[opaque_typedef(cmp(key = "str::len"))]
+pub struct ByLenStr(str);
+
+impl ByLenStr {
+    /// Creates a new `&ByLenStr` from the given string slice.
+    pub fn new(v: &str) -> &Self {
+        ::opaque_typedef::OpaqueTypedefUnsized::from_inner(v)
+    }
+}
+
+/// An owned string compared by its length.
+#[derive(Debug, Clone, OpaqueTypedef)]
+#[opaque_typedef(derive(AsRef(Deref), Deref, FromInner, IntoInner, PartialEq(Inner)))]
+#[opaque_typedef(cmp(key = "String::len"))]
+pub struct ByLenString(String);
@@ -0,0 +1,64 @@
+//! Types validated by checkers which borrow the inner values.
+
+/// A user name.
+#[derive(Debug, Clone, PartialEq, Eq, OpaqueTypedef)]
+#[opaque_typedef(derive(AsRef(Inner), Deref, IntoInner, TryFrom(Inner, InnerRef)))]
+#[opaque_typedef(validation(check = "check_username", error_type = "InvalidUsername"))]
+pub struct Username(String);
+
+/// A type of an error indicating the user name is invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidUsername {
+    /// The name is empty.
+    Empty,
+    /// The name has a character which is not ASCII alphanumeric.
+    InvalidChar(char),
+}
+
+fn check_username(s: &str) -> Result<(), InvalidUsername> {
+    if s.is_empty() {
+        return Err(InvalidUsername::Empty);
+    }
+    match s.chars().find(|c| !c.is_ascii_alphanumeric()) {
+        Some(c) => Err(InvalidUsername::InvalidChar(c)),
+        None => Ok(()),
+    }
+}
+
+/// A vector with at most 4 items.
+#[derive(Debug, Clone, PartialEq, Eq, OpaqueTypedef)]
+#[opaque_typedef(derive(AsRef(Inner), TryFromInnerRef))]
+#[opaque_typedef(validation(check = "check_at_most_4_items", error_type = "TooManyItems"))]
+pub struct VecAtMost4Items<T>(Vec<T>);
+
+/// A type of an error indicating the number of the items are too many.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooManyItems(pub usize);
+
+fn check_at_most_4_items<T>(v: &[T]) -> Result<(), TooManyItems> {
+    if v.len() <= 4 {
+        Ok(())
+    } else {
+        Err(TooManyItems(v.len()))
+    }
+}
+
+/// A string slice without uppercase letters.
+#[derive(Debug, PartialEq, Eq, OpaqueTypedefUnsized)]
+#[repr(C)]
+#[opaque_typedef(derive(AsRef(Inner), TryFromInner))]
+#[opaque_typedef(allow_mut_ref)]
+#[opaque_typedef(validation(check = "check_lowercase", error_type = "HasUppercase"))]
+pub struct LowerStr(str);
+
+/// A type of an error indicating the string has uppercase letters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HasUppercase;
+
+fn check_lowercase(s: &str) -> Result<(), HasUppercase> {
+    if s.chars().any(char::is_uppercase) {
+        Err(HasUppercase)
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+//! Types with forced `Debug` impls.
+
+/// A string slice formatted exactly like the inner string.
+#[derive(OpaqueTypedefUnsized)]
+#[repr(C)]
+#[opaque_typedef(derive(DebugTransparent, FromInner))]
+pub struct TransparentStr(str);
+
+impl TransparentStr {
+    /// Creates a new `&TransparentStr` from the given string slice.
+    pub fn new(v: &str) -> &Self {
+        ::opaque_typedef::OpaqueTypedefUnsized::from_inner(v)
+    }
+}
+
+/// An integer formatted as `NamedI32(inner)`, though the field is named.
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(derive(Debug(Named), FromInner))]
+pub struct NamedI32 {
+    inner: i32,
+}
+
+/// A vector formatted as the slice it dereferences to.
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(derive(Debug(Deref), Deref, FromInner))]
+#[opaque_typedef(deref(target = "[T]", deref = "Vec::<T>::as_slice"))]
+pub struct DerefVec<T>(Vec<T>);
@@ -0,0 +1,54 @@
+//! Types formatted and compared through their deref targets.
+
+use std::fmt;
+
+/// A byte slice formatted as hexadecimal digits.
+#[derive(Debug, PartialEq, OpaqueTypedefUnsized)]
+#[repr(C)]
+pub struct HexBytes([u8]);
+
+impl HexBytes {
+    /// Creates a new `&HexBytes` from the given bytes.
+    pub fn new<T: AsRef<[u8]> + ?Sized>(v: &T) -> &Self {
+        ::opaque_typedef::OpaqueTypedefUnsized::from_inner(v.as_ref())
+    }
+}
+
+impl fmt::LowerHex for HexBytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for HexBytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+/// A digest, which is formatted as hexadecimal digits.
+#[derive(Debug, Clone, OpaqueTypedef)]
+#[opaque_typedef(derive(
+    Deref,
+    Display(Deref),
+    FromInner,
+    LowerHex(Deref),
+    PartialEq(Deref, DerefRev)
+))]
+#[opaque_typedef(deref(target = "HexBytes", deref = "HexBytes::new"))]
+pub struct Digest(Vec<u8>);
+
+/// A name, which is comparable with string slices.
+#[derive(Debug, Clone, OpaqueTypedef)]
+#[opaque_typedef(derive(
+    AsRef(Deref),
+    Deref,
+    FromInner,
+    PartialEq(Deref, DerefRev),
+    PartialOrd(Deref, DerefRev)
+))]
+#[opaque_typedef(deref(target = "str", deref = "String::as_str"))]
+pub struct Name(String);
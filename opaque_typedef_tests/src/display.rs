@@ -0,0 +1,26 @@
+//! Types with custom `Display` impls.
+
+use std::fmt;
+
+/// Milliseconds, displayed with the unit suffix.
+#[derive(Debug, Clone, Copy, OpaqueTypedef)]
+#[opaque_typedef(derive(Display, FromInner, IntoInner))]
+#[opaque_typedef(display_fmt = "{}ms")]
+pub struct Millis(u64);
+
+/// A value displayed in braces and right-aligned.
+#[derive(Debug, Clone, Copy, OpaqueTypedef)]
+#[opaque_typedef(derive(Display, FromInner))]
+#[opaque_typedef(display_fmt = "{{{:>4}}}")]
+pub struct Braced(i32);
+
+/// A user ID, displayed with the prefix.
+#[derive(Debug, Clone, OpaqueTypedef)]
+#[opaque_typedef(derive(Display, FromInner))]
+#[opaque_typedef(display = "fmt_user_id")]
+pub struct UserId(String);
+
+/// Formats the user ID.
+fn fmt_user_id(v: &str, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "user:{}", v)
+}
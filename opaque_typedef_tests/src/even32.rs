@@ -5,7 +5,7 @@
 #[opaque_typedef(derive(
     Add(Self),
     AddRef(Self),
-    AddAssign(Self),
+    AddAssign(Self, Inner),
     AddAssignRef(Self),
     Binary,
     Deref,
@@ -0,0 +1,42 @@
+//! Types parsed from strings.
+
+use std::error;
+use std::fmt;
+
+/// A non-zero port number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, OpaqueTypedef)]
+#[opaque_typedef(derive(Display, FromStr, IntoInner))]
+#[opaque_typedef(validation(validator = "validate_port", error_type = "ZeroPortError"))]
+pub struct Port(u16);
+
+/// A type of an error indicating the port number is zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZeroPortError;
+
+impl fmt::Display for ZeroPortError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("port number should not be zero")
+    }
+}
+
+impl error::Error for ZeroPortError {}
+
+fn validate_port(v: u16) -> Result<u16, ZeroPortError> {
+    if v == 0 {
+        Err(ZeroPortError)
+    } else {
+        Ok(v)
+    }
+}
+
+/// Seconds, displayed and parsed with the unit suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, OpaqueTypedef)]
+#[opaque_typedef(derive(Display, FromInner, FromStr, IntoInner))]
+#[opaque_typedef(display_fmt = "{}s")]
+pub struct Seconds(u32);
+
+/// A user name, displayed and parsed with the prefix.
+#[derive(Debug, Clone, PartialEq, Eq, OpaqueTypedef)]
+#[opaque_typedef(derive(Display, FromInner, FromStr))]
+#[opaque_typedef(display_fmt = "user:{}")]
+pub struct UserName(String);
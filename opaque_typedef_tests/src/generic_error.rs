@@ -0,0 +1,42 @@
+//! Types with generic validation error types.
+
+use std::marker::PhantomData;
+
+/// A non-empty vector.
+#[derive(Debug, Clone, PartialEq, OpaqueTypedef)]
+#[opaque_typedef(derive(AsRef(Inner), Deref, IntoInner, PartialEq(Inner), TryFromInner))]
+#[opaque_typedef(validation(validator = "ensure_nonempty_vec", error_type = "EmptyVec<T>"))]
+pub struct NonEmptyVec<T>(Vec<T>);
+
+/// A type of an error indicating the vector is empty.
+///
+/// This holds the rejected vector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmptyVec<T>(pub Vec<T>);
+
+fn ensure_nonempty_vec<T>(v: Vec<T>) -> Result<Vec<T>, EmptyVec<T>> {
+    if v.is_empty() {
+        Err(EmptyVec(v))
+    } else {
+        Ok(v)
+    }
+}
+
+/// A non-empty slice.
+#[derive(Debug, PartialEq, OpaqueTypedefUnsized)]
+#[repr(C)]
+#[opaque_typedef(derive(AsRef(Inner), Deref, FromInner))]
+#[opaque_typedef(validation(validator = "ensure_nonempty_slice", error_type = "EmptySlice<T>"))]
+pub struct NonEmptySlice<T>([T]);
+
+/// A type of an error indicating the slice is empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmptySlice<T>(PhantomData<T>);
+
+fn ensure_nonempty_slice<T, U: AsRef<[T]>>(v: U) -> Result<U, EmptySlice<T>> {
+    if v.as_ref().is_empty() {
+        Err(EmptySlice(PhantomData))
+    } else {
+        Ok(v)
+    }
+}
@@ -6,8 +6,29 @@ extern crate opaque_typedef;
 extern crate opaque_typedef_macros;
 
 pub mod all_eq;
+pub mod ascii_caseless;
 pub mod atleast2items;
+pub mod auto_error;
+pub mod by_len;
+pub mod check;
+pub mod debug;
+pub mod deref_target;
+pub mod display;
 pub mod even32;
+pub mod from_str;
+pub mod generic_error;
 pub mod int32;
+pub mod my_bytes;
 pub mod my_str;
+pub mod nibble;
+pub mod nonempty;
+pub mod normalize;
+pub mod not_nan;
+pub mod percent;
+pub mod quantity;
+pub mod rejected;
 pub mod reverse_order;
+pub mod secret;
+pub mod secret_token;
+pub mod uncased;
+pub mod validator;
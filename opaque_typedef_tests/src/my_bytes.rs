@@ -0,0 +1,28 @@
+//! Opaque typedef for `[u8]`, with operators implemented by functions.
+
+/// My byte slice.
+#[derive(Debug, PartialEq, Eq, OpaqueTypedefUnsized)]
+#[repr(C)]
+#[opaque_typedef(derive(AsRef(Inner), BitXorRef(Self, Inner), FromInner, NotRef))]
+// `Vec<u8>` implements neither `BitXor` nor `Not`.
+#[opaque_typedef(ops(bitxor = "xor_bytes", not = "invert_bytes"))]
+pub struct MyBytes([u8]);
+
+impl MyBytes {
+    /// Creates a new `&MyBytes` from the given byte slice.
+    pub fn new(v: &[u8]) -> &Self {
+        ::opaque_typedef::OpaqueTypedefUnsized::from_inner(v)
+    }
+}
+
+/// Returns the bytewise XOR of the given slices.
+///
+/// The result is as long as the shorter slice.
+fn xor_bytes(lhs: &[u8], rhs: &[u8]) -> Vec<u8> {
+    lhs.iter().zip(rhs).map(|(l, r)| l ^ r).collect()
+}
+
+/// Returns the bytewise NOT of the given slice.
+fn invert_bytes(v: &[u8]) -> Vec<u8> {
+    v.iter().map(|b| !b).collect()
+}
@@ -10,6 +10,7 @@
 // About the necessity of `#[repr(C)]`, see <https://github.com/lo48576/opaque_typedef/issues/1>.
 #[repr(C)]
 #[opaque_typedef(derive(
+    AddRef(Self, Inner),
     AsMut(Deref, Self),
     AsRef(Deref, Self),
     DefaultRef,
@@ -22,6 +23,8 @@
     PartialOrd(Inner, InnerRev, InnerCow, InnerCowRev, SelfCow, SelfCowRev)
 ))]
 #[opaque_typedef(allow_mut_ref)]
+#[opaque_typedef(owned = "MyString")]
+#[opaque_typedef(ops(output = "inner"))]
 pub struct MyStr {
     #[opaque_typedef(inner)]
     inner: str,
@@ -45,12 +48,11 @@ impl MyStr {
 }
 
 /// My owned string.
+// `Deref<Target = MyStr>` is implemented by `#[opaque_typedef(owned = "MyString")]` of `MyStr`.
 #[derive(Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, OpaqueTypedef)]
 #[opaque_typedef(derive(
     AsMut(Deref, Inner),
     AsRef(Deref, Inner),
-    Deref,
-    DerefMut,
     Display,
     FromInner,
     IntoInner,
@@ -63,6 +65,7 @@ impl MyStr {
     deref_mut = "String::as_mut_str"
 ))]
 #[opaque_typedef(allow_mut_ref)]
+#[opaque_typedef(borrowed = "MyStr")]
 pub struct MyString {
     inner: String,
 }
@@ -83,16 +86,3 @@ impl MyString {
         self.inner.as_mut_str()
     }
 }
-
-// Implement `Borrow` and `ToOwned` to test `Cow<Mystr>`.
-impl ::std::borrow::Borrow<MyStr> for MyString {
-    fn borrow(&self) -> &MyStr {
-        MyStr::new(self.as_str())
-    }
-}
-impl ToOwned for MyStr {
-    type Owned = MyString;
-    fn to_owned(&self) -> Self::Owned {
-        MyString::from_string(self.as_str().to_owned())
-    }
-}
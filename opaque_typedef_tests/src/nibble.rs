@@ -0,0 +1,38 @@
+//! Opaque typedef for 4-bit unsigned integers, whose operators wrap around.
+
+/// 4-bit unsigned integer (`0..16`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, OpaqueTypedef)]
+#[opaque_typedef(derive(
+    Add(Self),
+    AddAssign(Self),
+    Mul(Inner),
+    Neg,
+    Sub(Self),
+    Display,
+    FromInner
+))]
+#[opaque_typedef(validation(
+    validator = "validate_nibble",
+    error_type = "OutOfRangeError",
+    error_msg = "Failed to create `Nibble`"
+))]
+#[opaque_typedef(ops(on_invalid = "wrap", wrap = "wrap_nibble"))]
+pub struct Nibble(i8);
+
+/// A type of an error indicating the value is out of `0..16`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OutOfRangeError;
+
+fn validate_nibble(v: i8) -> Result<i8, OutOfRangeError> {
+    if (0..16).contains(&v) {
+        Ok(v)
+    } else {
+        Err(OutOfRangeError)
+    }
+}
+
+// The inner values are wrapped at the bounds of `i8`, and 16 divides 256, so
+// the result is correct modulo 16.
+fn wrap_nibble(v: i8) -> i8 {
+    v & 0x0f
+}
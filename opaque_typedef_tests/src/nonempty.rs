@@ -0,0 +1,98 @@
+//! Opaque typedef for non-empty strings.
+
+/// Non-empty string slice.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, OpaqueTypedefUnsized)]
+// About the necessity of `#[repr(C)]`, see <https://github.com/lo48576/opaque_typedef/issues/1>.
+#[repr(C)]
+#[opaque_typedef(derive(
+    AddRef(Self, Inner, InnerRev),
+    AsRef(Deref),
+    Deref,
+    Deserialize,
+    Display,
+    IntoInner,
+    Serialize,
+    TryFromInner
+))]
+#[opaque_typedef(validation(
+    validator = "ensure_nonempty",
+    error_type = "EmptyError",
+    error_msg = "Failed to create `NonEmptyStr`"
+))]
+#[opaque_typedef(owned = "NonEmptyString")]
+pub struct NonEmptyStr(str);
+
+impl NonEmptyStr {
+    /// Returns a reference to the inner string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Non-empty owned string.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, OpaqueTypedef)]
+#[opaque_typedef(derive(Deserialize, Display, IntoInner, Serialize, TryFrom(Inner)))]
+#[opaque_typedef(validation(
+    validator = "ensure_nonempty",
+    error_type = "EmptyError",
+    error_msg = "Failed to create `NonEmptyString`"
+))]
+pub struct NonEmptyString(String);
+
+impl NonEmptyString {
+    /// Returns a reference to the inner string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Non-empty slice.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, OpaqueTypedefUnsized)]
+// About the necessity of `#[repr(C)]`, see <https://github.com/lo48576/opaque_typedef/issues/1>.
+#[repr(C)]
+#[opaque_typedef(derive(AsRef(Deref), Deref, IntoInner, TryFromInner))]
+#[opaque_typedef(validation(
+    validator = "ensure_nonempty_slice",
+    error_type = "EmptyError",
+    error_msg = "Failed to create `NonEmptySlice`"
+))]
+#[opaque_typedef(owned = "NonEmptyVec<T>")]
+pub struct NonEmptySlice<T>([T]);
+
+impl<T> NonEmptySlice<T> {
+    /// Returns a reference to the inner slice.
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+}
+
+/// Non-empty vector.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, OpaqueTypedef)]
+#[opaque_typedef(derive(Deserialize, IntoInner, Serialize, TryFrom(Inner)))]
+#[opaque_typedef(validation(
+    validator = "ensure_nonempty_slice",
+    error_type = "EmptyError",
+    error_msg = "Failed to create `NonEmptyVec`"
+))]
+#[opaque_typedef(borrowed = "NonEmptySlice<T>")]
+pub struct NonEmptyVec<T>(Vec<T>);
+
+/// A type of an error indicating the string is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EmptyError;
+
+fn ensure_nonempty<T: AsRef<str>>(s: T) -> Result<T, EmptyError> {
+    if s.as_ref().is_empty() {
+        Err(EmptyError)
+    } else {
+        Ok(s)
+    }
+}
+
+fn ensure_nonempty_slice<U, T: AsRef<[U]>>(v: T) -> Result<T, EmptyError> {
+    if v.as_ref().is_empty() {
+        Err(EmptyError)
+    } else {
+        Ok(v)
+    }
+}
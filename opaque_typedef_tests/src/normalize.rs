@@ -0,0 +1,106 @@
+//! Types normalizing the inner values before validation.
+
+use std::borrow::Cow;
+
+/// A host name slice, normalized to lowercase.
+#[derive(Debug, PartialEq, Eq, OpaqueTypedefUnsized)]
+#[repr(C)]
+#[opaque_typedef(derive(AsRef(Inner), Display, TryFromInner))]
+#[opaque_typedef(validation(
+    normalize = "lowercase_str",
+    not_normalized = "has_uppercase",
+    check = "check_hostname",
+    error_type = "InvalidHostname"
+))]
+#[opaque_typedef(owned = "Hostname")]
+pub struct HostnameStr(str);
+
+impl HostnameStr {
+    /// Returns a reference to the inner string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// An owned host name, normalized to lowercase.
+#[derive(Debug, Clone, PartialEq, Eq, OpaqueTypedef)]
+#[opaque_typedef(derive(Display, IntoInner, TryFrom(Inner, InnerRef)))]
+#[opaque_typedef(validation(
+    normalize = "lowercase_string",
+    check = "check_hostname",
+    error_type = "InvalidHostname"
+))]
+pub struct Hostname(String);
+
+/// A type of an error indicating the host name is invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidHostname;
+
+fn check_hostname(s: &str) -> Result<(), InvalidHostname> {
+    let is_valid_char =
+        |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.';
+    if !s.is_empty() && s.chars().all(is_valid_char) {
+        Ok(())
+    } else {
+        Err(InvalidHostname)
+    }
+}
+
+fn lowercase_str(s: &str) -> Cow<'_, str> {
+    if s.chars().any(|c| c.is_ascii_uppercase()) {
+        Cow::Owned(s.to_ascii_lowercase())
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+fn has_uppercase(_: &str) -> InvalidHostname {
+    InvalidHostname
+}
+
+fn lowercase_string(mut s: String) -> String {
+    s.make_ascii_lowercase();
+    s
+}
+
+/// A trimmed string without a validator.
+#[derive(Debug, Clone, PartialEq, Eq, OpaqueTypedef)]
+#[opaque_typedef(derive(AsRef(Inner), FromInner))]
+#[opaque_typedef(validation(normalize = "trim"))]
+pub struct Trimmed(String);
+
+fn trim(s: String) -> String {
+    if s.trim() == s {
+        s
+    } else {
+        s.trim().to_owned()
+    }
+}
+
+/// A trimmed string slice without a validator.
+#[derive(Debug, PartialEq, Eq, OpaqueTypedefUnsized)]
+#[repr(C)]
+#[opaque_typedef(derive(AsRef(Inner), TryFromInner))]
+#[opaque_typedef(validation(
+    normalize = "trim_str",
+    not_normalized = "not_trimmed",
+    error_type = "NotTrimmed"
+))]
+#[opaque_typedef(owned = "Trimmed")]
+pub struct TrimmedStr(str);
+
+/// A type of an error indicating the string is not trimmed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotTrimmed;
+
+fn trim_str(s: &str) -> Cow<'_, str> {
+    if s.trim() == s {
+        Cow::Borrowed(s)
+    } else {
+        Cow::Owned(s.trim().to_owned())
+    }
+}
+
+fn not_trimmed(_: &str) -> NotTrimmed {
+    NotTrimmed
+}
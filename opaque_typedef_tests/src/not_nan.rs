@@ -0,0 +1,27 @@
+//! Opaque typedefs for floating point numbers which are not NaN.
+
+/// `f64` which is not NaN.
+#[derive(Debug, Default, Clone, Copy, OpaqueTypedef)]
+#[opaque_typedef(derive(
+    Add(Self, Inner),
+    AddAssign(Self),
+    Div(Self, Inner),
+    Mul(Self, Inner),
+    Neg,
+    Sub(Self, Inner),
+    Display,
+    FromInner,
+    IntoInner,
+    PartialEq(Inner),
+    PartialOrd(Inner)
+))]
+#[opaque_typedef(float(not_nan))]
+#[opaque_typedef(validation(error_msg = "Failed to create `NotNan64`"))]
+pub struct NotNan64(f64);
+
+/// `f32` which is not NaN, whose operators return `Result`.
+#[derive(Debug, Default, Clone, Copy, OpaqueTypedef)]
+#[opaque_typedef(derive(Add(Self), Div(Self), Display, FromInner, IntoInner))]
+#[opaque_typedef(float(not_nan))]
+#[opaque_typedef(ops(on_invalid = "checked"))]
+pub struct CheckedNotNan32(f32);
@@ -0,0 +1,39 @@
+//! Opaque typedefs for percentages, with policies for invalid operator results.
+
+/// Percentage (`0..=100`), whose operators return `Result`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, OpaqueTypedef)]
+#[opaque_typedef(derive(Add(Self), AddRef(Self), Mul(Inner), Display, FromInner))]
+#[opaque_typedef(validation(
+    validator = "validate_percent",
+    error_type = "OutOfRangeError",
+    error_msg = "Failed to create `CheckedPercent`"
+))]
+#[opaque_typedef(ops(on_invalid = "checked"))]
+pub struct CheckedPercent(u8);
+
+/// Percentage (`0..=100`), whose operators saturate the result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, OpaqueTypedef)]
+#[opaque_typedef(derive(Add(Self), AddAssign(Self), Mul(Inner), Display, FromInner))]
+#[opaque_typedef(validation(
+    validator = "validate_percent",
+    error_type = "OutOfRangeError",
+    error_msg = "Failed to create `SaturatingPercent`"
+))]
+#[opaque_typedef(ops(on_invalid = "saturate", clamp = "clamp_percent"))]
+pub struct SaturatingPercent(u8);
+
+/// A type of an error indicating the value is greater than 100.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OutOfRangeError;
+
+fn validate_percent(v: u8) -> Result<u8, OutOfRangeError> {
+    if v <= 100 {
+        Ok(v)
+    } else {
+        Err(OutOfRangeError)
+    }
+}
+
+fn clamp_percent(v: u8) -> u8 {
+    v.min(100)
+}
@@ -0,0 +1,81 @@
+//! Opaque typedefs with multiple fields.
+
+use std::marker::PhantomData;
+
+/// Unit of length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Meter {}
+
+/// Unit of time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Second {}
+
+/// `f64` with the unit.
+#[derive(Debug, Clone, Copy, PartialEq, OpaqueTypedef)]
+#[opaque_typedef(derive(
+    Add(Self),
+    Sub(Self),
+    Mul(Inner),
+    Display,
+    FromInner,
+    IntoInner,
+    PartialEq(Inner, InnerRev)
+))]
+pub struct Quantity<U> {
+    /// Value.
+    #[opaque_typedef(inner)]
+    value: f64,
+    /// Unit.
+    _unit: PhantomData<U>,
+}
+
+impl<U> Quantity<U> {
+    /// Returns the value.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+}
+
+/// `i32` with a label.
+#[derive(Debug, Clone, PartialEq, Eq, OpaqueTypedef)]
+#[opaque_typedef(derive(Display, FromInner, IntoInner))]
+pub struct Labeled(
+    #[opaque_typedef(default = "\"unlabeled\"")] &'static str,
+    #[opaque_typedef(inner)] i32,
+);
+
+impl Labeled {
+    /// Returns the label.
+    pub fn label(&self) -> &'static str {
+        self.0
+    }
+
+    /// Returns the value.
+    pub fn value(&self) -> i32 {
+        self.1
+    }
+}
+
+/// String slice with the tag type.
+#[derive(Debug, PartialEq, Eq, OpaqueTypedefUnsized)]
+#[repr(transparent)]
+#[opaque_typedef(derive(AsRef(Inner), Display, FromInner, Into(Box, Inner)))]
+pub struct TaggedStr<T> {
+    /// Tag.
+    _tag: PhantomData<T>,
+    /// Inner string slice.
+    #[opaque_typedef(inner)]
+    inner: str,
+}
+
+impl<T> TaggedStr<T> {
+    /// Creates a new `TaggedStr` from the given string slice.
+    pub fn new(s: &str) -> &Self {
+        ::opaque_typedef::OpaqueTypedefUnsized::from_inner(s)
+    }
+
+    /// Returns the string slice.
+    pub fn as_str(&self) -> &str {
+        &self.inner
+    }
+}
@@ -0,0 +1,37 @@
+//! Types whose validators hand the rejected values back.
+
+use opaque_typedef::Rejected;
+
+/// A vector with at least 3 items.
+#[derive(Debug, PartialEq, Eq, OpaqueTypedef)]
+#[opaque_typedef(derive(AsRef(Inner), Deref, IntoInner, TryFromInner))]
+#[opaque_typedef(validation(
+    validator = "ensure_at_least_3_items",
+    error_type = "TooShort",
+    error_msg = "Failed to create `VecAtLeast3Items`",
+    rejected
+))]
+pub struct VecAtLeast3Items<T>(Vec<T>);
+
+/// A type of an error indicating the vector is too short.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooShort(pub usize);
+
+fn ensure_at_least_3_items<T>(v: Vec<T>) -> Result<Vec<T>, Rejected<Vec<T>, TooShort>> {
+    if v.len() >= 3 {
+        Ok(v)
+    } else {
+        let len = v.len();
+        Err(Rejected::new(v, TooShort(len)))
+    }
+}
+
+/// A non-empty byte buffer.
+#[derive(Debug, PartialEq, Eq, OpaqueTypedef)]
+#[opaque_typedef(derive(AsRef(Inner), IntoInner))]
+#[opaque_typedef(validation(validator = "is_nonempty", error = "auto"))]
+pub struct NonEmptyBuf(Vec<u8>);
+
+fn is_nonempty(v: &[u8]) -> bool {
+    !v.is_empty()
+}
@@ -0,0 +1,20 @@
+//! Secret-bearing types with redacted formatting and zeroize-on-drop.
+
+/// A password.
+#[derive(Clone, OpaqueTypedef)]
+#[opaque_typedef(derive(FromInner, IntoInner, PartialEqSelf))]
+#[opaque_typedef(cmp(constant_time))]
+#[opaque_typedef(secret(allow_derive(IntoInner)))]
+pub struct Password(String);
+
+/// A secret key, whose bytes can be explicitly borrowed.
+#[derive(Clone, OpaqueTypedef)]
+#[opaque_typedef(derive(AsRef(Inner), FromInner))]
+#[opaque_typedef(secret(allow_derive(AsRef(Inner))))]
+pub struct SecretKey(Vec<u8>);
+
+/// A PIN code.
+#[derive(Clone, PartialEq, Eq, OpaqueTypedef)]
+#[opaque_typedef(derive(FromInner, IntoInner))]
+#[opaque_typedef(secret(allow_derive(IntoInner)))]
+pub struct Pin(u32);
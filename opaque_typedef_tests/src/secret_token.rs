@@ -0,0 +1,21 @@
+//! Secret-bearing types compared in constant time.
+
+/// An API token.
+#[derive(Debug, Clone, OpaqueTypedef)]
+#[opaque_typedef(derive(Eq, FromInner, Hash, PartialEq(Self, Inner, InnerRev)))]
+#[opaque_typedef(cmp(constant_time))]
+pub struct ApiToken(String);
+
+/// A MAC tag.
+#[derive(Debug, OpaqueTypedefUnsized)]
+#[repr(C)]
+#[opaque_typedef(derive(AsRef(Deref), Deref, FromInner, PartialEq(Self, Inner, InnerRev)))]
+#[opaque_typedef(cmp(constant_time))]
+pub struct MacTag([u8]);
+
+impl MacTag {
+    /// Creates a new `&MacTag` from the given bytes.
+    pub fn new(v: &[u8]) -> &Self {
+        ::opaque_typedef::OpaqueTypedefUnsized::from_inner(v)
+    }
+}
@@ -0,0 +1,37 @@
+//! String types compared case-insensitively for ASCII characters, by the
+//! `ascii_case_insensitive` preset.
+
+/// An ASCII case-insensitive string slice.
+#[derive(Debug, OpaqueTypedefUnsized)]
+#[repr(C)]
+#[opaque_typedef(derive(
+    AsRef(Deref),
+    Deref,
+    Display,
+    FromInner,
+    PartialEq(Inner, InnerRev, InnerCow, InnerCowRev),
+    PartialOrd(Inner, InnerRev, InnerCow, InnerCowRev)
+))]
+#[opaque_typedef(cmp(ascii_case_insensitive))]
+pub struct UncasedStr(str);
+
+impl UncasedStr {
+    /// Creates a new `&UncasedStr` from the given string slice.
+    pub fn new(v: &str) -> &Self {
+        ::opaque_typedef::OpaqueTypedefUnsized::from_inner(v)
+    }
+}
+
+/// An ASCII case-insensitive owned string.
+#[derive(Debug, Clone, OpaqueTypedef)]
+#[opaque_typedef(derive(
+    AsRef(Deref),
+    Deref,
+    Display,
+    FromInner,
+    IntoInner,
+    PartialEq(Inner, InnerRev),
+    PartialOrd(Inner, InnerRev)
+))]
+#[opaque_typedef(cmp(ascii_case_insensitive))]
+pub struct Uncased(String);
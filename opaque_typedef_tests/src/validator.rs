@@ -0,0 +1,76 @@
+//! Types validated by composable validators.
+
+use opaque_typedef::validator::{Either, Validator, ValidatorExt};
+
+/// A validator which rejects empty strings.
+#[derive(Debug, Clone, Copy)]
+pub struct NonEmpty;
+
+impl<T: AsRef<str> + ?Sized> Validator<T> for NonEmpty {
+    type Error = EmptyError;
+
+    fn validate(&self, value: &T) -> Result<(), Self::Error> {
+        if value.as_ref().is_empty() {
+            Err(EmptyError)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl ValidatorExt for NonEmpty {}
+
+/// A type of an error indicating the string is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmptyError;
+
+/// A validator which rejects strings longer than the given number of bytes.
+#[derive(Debug, Clone, Copy)]
+pub struct MaxLen(pub usize);
+
+impl<T: AsRef<str> + ?Sized> Validator<T> for MaxLen {
+    type Error = TooLong;
+
+    fn validate(&self, value: &T) -> Result<(), Self::Error> {
+        let len = value.as_ref().len();
+        if len > self.0 {
+            Err(TooLong { max: self.0, len })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl ValidatorExt for MaxLen {}
+
+/// A type of an error indicating the string is too long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooLong {
+    /// Maximum length.
+    pub max: usize,
+    /// Actual length.
+    pub len: usize,
+}
+
+/// A short name.
+#[derive(Debug, Clone, PartialEq, Eq, OpaqueTypedef)]
+#[opaque_typedef(derive(AsRef(Inner), TryFrom(Inner, InnerRef)))]
+#[opaque_typedef(validation(
+    with = "NonEmpty.and(MaxLen(8))",
+    error_type = "Either<EmptyError, TooLong>"
+))]
+pub struct ShortName(String);
+
+/// A short name slice.
+#[derive(Debug, PartialEq, Eq, OpaqueTypedefUnsized)]
+#[repr(C)]
+#[opaque_typedef(derive(AsRef(Inner), TryFromInner))]
+#[opaque_typedef(validation(
+    with = "NonEmpty.and(MaxLen(8)).map_err(|_| InvalidName)",
+    error_type = "InvalidName"
+))]
+pub struct ShortNameStr(str);
+
+/// A type of an error indicating the name is invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidName;
@@ -0,0 +1,38 @@
+//! A string type compared case-insensitively for ASCII characters.
+
+extern crate opaque_typedef;
+extern crate opaque_typedef_tests;
+
+mod common;
+
+use std::collections::HashSet;
+
+use opaque_typedef_tests::ascii_caseless::AsciiCaselessString;
+
+use common::assert_eq_with_hash;
+
+#[test]
+fn partial_eq() {
+    let lower = AsciiCaselessString::from("hello".to_owned());
+    let mixed = AsciiCaselessString::from("HeLLo".to_owned());
+    let other = AsciiCaselessString::from("world".to_owned());
+    assert_eq!(lower, mixed);
+    assert_ne!(lower, other);
+}
+
+#[test]
+fn hash_consistent_with_eq() {
+    let lower = AsciiCaselessString::from("hello".to_owned());
+    let mixed = AsciiCaselessString::from("HeLLo".to_owned());
+    assert_eq_with_hash(&lower, &mixed);
+}
+
+#[test]
+fn hash_set() {
+    let mut set = HashSet::new();
+    assert!(set.insert(AsciiCaselessString::from("hello".to_owned())));
+    assert!(!set.insert(AsciiCaselessString::from("HELLO".to_owned())));
+    assert!(set.insert(AsciiCaselessString::from("world".to_owned())));
+    assert!(set.contains(&AsciiCaselessString::from("World".to_owned())));
+    assert_eq!(set.len(), 2);
+}
@@ -0,0 +1,95 @@
+//! Types with generated validation error types.
+
+extern crate opaque_typedef;
+extern crate opaque_typedef_tests;
+
+use std::error::Error;
+
+use opaque_typedef::{
+    OpaqueTypedef, OpaqueTypedefStdError, OpaqueTypedefUnsized, OpaqueTypedefUnsizedStdError,
+};
+use opaque_typedef_tests::auto_error::{Even, EvenError, Word, WordError};
+
+fn create_sized<T: OpaqueTypedefStdError>(inner: T::Inner) -> Result<T, Box<dyn Error>> {
+    Ok(T::try_from_inner(inner)?)
+}
+
+fn create_unsized<T>(inner: &T::Inner) -> Result<&T, Box<dyn Error>>
+where
+    T: OpaqueTypedefUnsizedStdError + ?Sized,
+{
+    Ok(T::try_from_inner(inner)?)
+}
+
+mod sized {
+    use super::*;
+
+    #[test]
+    fn ok() {
+        let inner: i32 = Even::try_from_inner(42).unwrap().into();
+        assert_eq!(inner, 42);
+    }
+
+    #[test]
+    fn error() {
+        let err = Even::try_from_inner(3).unwrap_err();
+        assert_eq!(err.message(), "the number is odd");
+        assert_eq!(err.to_string(), "the number is odd");
+        assert_eq!(*err.value(), 3);
+        assert_eq!(err.clone(), err);
+        assert_eq!(err.into_value(), 3);
+    }
+
+    #[test]
+    fn boxed_error() {
+        assert!(create_sized::<Even>(42).is_ok());
+        let err = create_sized::<Even>(3).unwrap_err();
+        assert_eq!(err.to_string(), "the number is odd");
+    }
+
+    #[test]
+    #[should_panic(expected = "the number is odd")]
+    fn from_inner_panics() {
+        Even::from_inner(3);
+    }
+
+    #[test]
+    fn from_str() {
+        let err = "3".parse::<Even>().unwrap_err();
+        assert_eq!(err.to_string(), "invalid value: the number is odd");
+    }
+}
+
+mod unsized_ {
+    use super::*;
+
+    #[test]
+    fn ok() {
+        let word = Word::try_from_inner("foo").unwrap();
+        assert_eq!(word.as_ref(), "foo");
+        let mut s = "bar".to_owned();
+        assert!(Word::try_from_inner_mut(s.as_mut_str()).is_ok());
+    }
+
+    #[test]
+    fn error() {
+        let err: WordError = Word::try_from_inner("foo bar").unwrap_err();
+        assert_eq!(err.message(), "invalid `Word` value");
+        let mut s = "foo bar".to_owned();
+        assert!(Word::try_from_inner_mut(s.as_mut_str()).is_err());
+    }
+
+    #[test]
+    fn boxed_error() {
+        assert!(create_unsized::<Word>("foo").is_ok());
+        let err = create_unsized::<Word>("foo bar").unwrap_err();
+        assert_eq!(err.to_string(), "invalid `Word` value");
+    }
+}
+
+#[test]
+fn error_type() {
+    fn assert_error<E: Error + Clone + PartialEq>() {}
+    assert_error::<EvenError>();
+    assert_error::<WordError>();
+}
@@ -0,0 +1,95 @@
+//! String types compared by their lengths.
+
+extern crate opaque_typedef;
+extern crate opaque_typedef_tests;
+
+mod common;
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashSet};
+
+use opaque_typedef_tests::by_len::{ByLenStr, ByLenString};
+
+use common::assert_eq_with_hash;
+
+mod unsized_ {
+    use super::*;
+
+    #[test]
+    fn eq_self() {
+        assert_eq!(ByLenStr::new("abc"), ByLenStr::new("xyz"));
+        assert_ne!(ByLenStr::new("abc"), ByLenStr::new("abcd"));
+    }
+
+    #[test]
+    fn ord_self() {
+        assert_eq!(
+            ByLenStr::new("zz").cmp(ByLenStr::new("aaa")),
+            Ordering::Less
+        );
+        assert_eq!(
+            ByLenStr::new("zz").partial_cmp(ByLenStr::new("aa")),
+            Some(Ordering::Equal)
+        );
+    }
+
+    #[test]
+    fn hash_consistent_with_eq() {
+        assert_eq_with_hash(ByLenStr::new("abc"), ByLenStr::new("xyz"));
+    }
+
+    #[test]
+    fn eq_inner() {
+        assert!(ByLenStr::new("abc") == *"xyz");
+        assert!(*"xyz" == *ByLenStr::new("abc"));
+        assert!(ByLenStr::new("abc") != "abcd");
+    }
+
+    #[test]
+    fn ord_inner() {
+        assert!(ByLenStr::new("zz") < *"aaa");
+        assert!(*"aaa" > *ByLenStr::new("zz"));
+    }
+
+    #[test]
+    fn eq_cow() {
+        let cow: Cow<str> = Cow::Borrowed("xyz");
+        assert!(*ByLenStr::new("abc") == cow);
+        assert!(cow == *ByLenStr::new("abc"));
+    }
+}
+
+mod sized {
+    use super::*;
+
+    #[test]
+    fn eq_self() {
+        assert_eq!(
+            ByLenString::from("abc".to_owned()),
+            ByLenString::from("xyz".to_owned())
+        );
+        let outer = ByLenString::from("abc".to_owned());
+        let inner = "xyz".to_owned();
+        assert!(outer == inner);
+    }
+
+    #[test]
+    fn btree_set() {
+        let set = ["ccc", "a", "bb", "zz"]
+            .iter()
+            .map(|&s| ByLenString::from(s.to_owned()))
+            .collect::<BTreeSet<_>>();
+        let lens = set.iter().map(|s| s.len()).collect::<Vec<_>>();
+        assert_eq!(lens, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn hash_set() {
+        let mut set = HashSet::new();
+        assert!(set.insert(ByLenString::from("abc".to_owned())));
+        assert!(!set.insert(ByLenString::from("xyz".to_owned())));
+        assert!(set.contains(&ByLenString::from("123".to_owned())));
+        assert!(!set.contains(&ByLenString::from("1234".to_owned())));
+    }
+}
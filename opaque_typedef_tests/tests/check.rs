@@ -0,0 +1,97 @@
+//! Types validated by checkers which borrow the inner values.
+
+extern crate opaque_typedef;
+extern crate opaque_typedef_tests;
+
+use std::convert::TryFrom;
+
+use opaque_typedef::{OpaqueTypedef, OpaqueTypedefUnsized};
+use opaque_typedef_tests::auto_error::Even;
+use opaque_typedef_tests::check::{
+    HasUppercase, InvalidUsername, LowerStr, TooManyItems, Username, VecAtMost4Items,
+};
+use opaque_typedef_tests::even32::{Even32, OddError};
+
+#[test]
+fn validate() {
+    assert_eq!(Username::validate(&"alice".to_owned()), Ok(()));
+    assert_eq!(
+        Username::validate(&String::new()),
+        Err(InvalidUsername::Empty)
+    );
+    assert_eq!(
+        Username::validate(&"bob!".to_owned()),
+        Err(InvalidUsername::InvalidChar('!'))
+    );
+}
+
+#[test]
+fn try_from_inner() {
+    let name = Username::try_from("alice".to_owned()).unwrap();
+    assert_eq!(&*name, "alice");
+    assert_eq!(
+        Username::try_from(String::new()),
+        Err(InvalidUsername::Empty)
+    );
+}
+
+#[test]
+fn try_from_inner_ref() {
+    let input = "alice".to_owned();
+    let name = Username::try_from(&input).unwrap();
+    assert_eq!(name.as_ref(), &input);
+    assert_eq!(
+        Username::try_from(&"a b".to_owned()),
+        Err(InvalidUsername::InvalidChar(' '))
+    );
+}
+
+#[test]
+fn or_return() {
+    let (v, e) = Username::try_from_inner_or_return(String::with_capacity(16)).unwrap_err();
+    assert_eq!(e, InvalidUsername::Empty);
+    assert_eq!(v.capacity(), 16);
+}
+
+#[test]
+#[should_panic]
+fn from_inner_panics() {
+    let _ = Username::from_inner("a-b".to_owned());
+}
+
+#[test]
+fn generic() {
+    let v = vec![1, 2, 3];
+    let outer = VecAtMost4Items::try_from(&v).unwrap();
+    assert_eq!(outer.as_ref(), &v);
+    assert_eq!(VecAtMost4Items::try_from(&vec![0; 5]), Err(TooManyItems(5)));
+    assert_eq!(
+        VecAtMost4Items::<u8>::validate(&vec![0; 6]),
+        Err(TooManyItems(6))
+    );
+}
+
+#[test]
+fn unsized_() {
+    assert_eq!(LowerStr::validate("foo"), Ok(()));
+    assert_eq!(LowerStr::validate("Foo"), Err(HasUppercase));
+    let s = <&LowerStr>::try_from("foo").unwrap();
+    assert_eq!(s.as_ref(), "foo");
+    let mut buf = "Foo".to_owned();
+    assert_eq!(
+        <&mut LowerStr>::try_from(buf.as_mut_str()),
+        Err(HasUppercase)
+    );
+}
+
+#[test]
+fn auto_error() {
+    assert!(Even::validate(&2).is_ok());
+    assert_eq!(Even::validate(&3).unwrap_err().into_value(), 3);
+}
+
+#[test]
+fn default_validate() {
+    assert_eq!(Even32::validate(&2), Ok(()));
+    assert_eq!(Even32::validate(&3), Err(OddError));
+}
@@ -0,0 +1,27 @@
+//! Helpers shared by tests.
+
+// Each test crate uses only some of the helpers.
+#![allow(dead_code)]
+
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+
+/// Returns the hash of the value.
+pub fn hash_of<T: Hash + ?Sized>(v: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    v.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Asserts that the values are equal and their hashes are also equal.
+pub fn assert_eq_with_hash<T: Debug + Hash + PartialEq + ?Sized>(lhs: &T, rhs: &T) {
+    assert_eq!(lhs, rhs);
+    assert_eq!(
+        hash_of(lhs),
+        hash_of(rhs),
+        "Hashes of equal values should be equal: lhs = {:?}, rhs = {:?}",
+        lhs,
+        rhs
+    );
+}
@@ -0,0 +1,24 @@
+//! Types with forced `Debug` impls.
+
+extern crate opaque_typedef_tests;
+
+use opaque_typedef_tests::debug::{DerefVec, NamedI32, TransparentStr};
+
+#[test]
+fn transparent() {
+    let v = TransparentStr::new("foo\n");
+    assert_eq!(format!("{:?}", v), format!("{:?}", "foo\n"));
+}
+
+#[test]
+fn named() {
+    let v = NamedI32::from(42);
+    assert_eq!(format!("{:?}", v), "NamedI32(42)");
+    assert_eq!(format!("{:#?}", v), "NamedI32(\n    42,\n)");
+}
+
+#[test]
+fn deref() {
+    let v = DerefVec::from(vec![1, 2, 3]);
+    assert_eq!(format!("{:?}", v), "[1, 2, 3]");
+}
@@ -0,0 +1,57 @@
+//! Types formatted and compared through their deref targets.
+
+extern crate opaque_typedef_tests;
+
+use opaque_typedef_tests::deref_target::{Digest, HexBytes, Name};
+
+mod digest {
+    use super::*;
+
+    #[test]
+    fn fmt() {
+        let digest = Digest::from(vec![0x01, 0xab, 0xff]);
+        assert_eq!(format!("{}", digest), "01abff");
+        assert_eq!(format!("{:x}", digest), "01abff");
+    }
+
+    #[test]
+    fn eq_deref() {
+        let digest = Digest::from(vec![0x01, 0xab]);
+        assert!(digest == *HexBytes::new(&[0x01, 0xab]));
+        assert!(*HexBytes::new(&[0x01, 0xab]) == digest);
+        assert!(digest != HexBytes::new(&[0x01]));
+        assert!(HexBytes::new(&[0x01]) != digest);
+    }
+}
+
+mod name {
+    use super::*;
+
+    #[test]
+    fn eq_deref() {
+        let name = Name::from("abc".to_owned());
+        assert!(name == *"abc");
+        assert!(name == "abc");
+        assert!(&name == "abc");
+        assert!(*"abc" == name);
+        assert!("abc" == name);
+        assert!("abc" == &name);
+        assert!(name != "abcd");
+    }
+
+    #[test]
+    fn ord_deref() {
+        let name = Name::from("abc".to_owned());
+        assert!(name < *"abd");
+        assert!(name > "abb");
+        assert!("abb" < name);
+        assert!(*"abd" > name);
+    }
+
+    #[test]
+    fn as_ref_deref() {
+        let name = Name::from("abc".to_owned());
+        let s: &str = name.as_ref();
+        assert_eq!(s, "abc");
+    }
+}
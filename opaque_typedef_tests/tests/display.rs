@@ -0,0 +1,20 @@
+//! Types with custom `Display` impls.
+
+extern crate opaque_typedef_tests;
+
+use opaque_typedef_tests::display::{Braced, Millis, UserId};
+
+#[test]
+fn display_fmt() {
+    assert_eq!(Millis::from(42).to_string(), "42ms");
+}
+
+#[test]
+fn display_fmt_escaped() {
+    assert_eq!(Braced::from(42).to_string(), "{  42}");
+}
+
+#[test]
+fn display_fn() {
+    assert_eq!(UserId::from("alice".to_owned()).to_string(), "user:alice");
+}
@@ -218,4 +218,20 @@ mod ops {
         x += &y;
         assert_eq!(x, sum);
     }
+
+    #[test]
+    fn add_assign_inner() {
+        let mut x = Even32::from(10);
+        x += 32;
+        assert_eq!(x, Even32::from(42));
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_assign_inner_odd() {
+        // `AddAssign` is implemented through the validated constructor by default
+        // for types with validator.
+        let mut x = Even32::from(10);
+        x += 1;
+    }
 }
@@ -0,0 +1,67 @@
+//! Types parsed from strings.
+
+extern crate opaque_typedef;
+extern crate opaque_typedef_tests;
+
+use std::error::Error;
+use std::num::ParseIntError;
+
+use opaque_typedef::ParseError;
+use opaque_typedef_tests::from_str::{Port, Seconds, UserName, ZeroPortError};
+
+fn parse_int_error() -> ParseIntError {
+    "x".parse::<u16>().unwrap_err()
+}
+
+mod port {
+    use super::*;
+
+    #[test]
+    fn ok() {
+        let port = "8080".parse::<Port>().unwrap();
+        let inner: u16 = port.into();
+        assert_eq!(inner, 8080);
+    }
+
+    #[test]
+    fn inner_error() {
+        let err = "http".parse::<Port>().unwrap_err();
+        assert_eq!(err, ParseError::Inner(parse_int_error()));
+        assert!(err.source().is_some());
+        assert!(err
+            .to_string()
+            .starts_with("failed to parse the inner value: "));
+    }
+
+    #[test]
+    fn validation_error() {
+        let err = "0".parse::<Port>().unwrap_err();
+        assert_eq!(err, ParseError::Validation(ZeroPortError));
+        assert_eq!(
+            err.to_string(),
+            "invalid value: port number should not be zero"
+        );
+    }
+}
+
+mod display_fmt {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let secs = Seconds::from(42);
+        assert_eq!(secs.to_string().parse::<Seconds>(), Ok(secs));
+        let name = UserName::from("alice".to_owned());
+        assert_eq!(name.to_string().parse::<UserName>(), Ok(name));
+    }
+
+    #[test]
+    fn format_error() {
+        assert_eq!("42".parse::<Seconds>(), Err(ParseError::Format));
+        assert_eq!("alice".parse::<UserName>(), Err(ParseError::Format));
+        assert_eq!(
+            "xs".parse::<Seconds>(),
+            Err(ParseError::Inner(parse_int_error()))
+        );
+    }
+}
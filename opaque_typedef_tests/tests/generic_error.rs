@@ -0,0 +1,58 @@
+//! Types with generic validation error types.
+
+extern crate opaque_typedef;
+extern crate opaque_typedef_tests;
+
+use std::convert::TryFrom;
+use std::ops::Deref;
+
+use opaque_typedef::{OpaqueTypedef, OpaqueTypedefUnsized};
+use opaque_typedef_tests::generic_error::{EmptyVec, NonEmptySlice, NonEmptyVec};
+
+#[test]
+fn sized() {
+    let v = NonEmptyVec::try_from(vec![1, 2]).unwrap();
+    assert!(v == vec![1, 2]);
+    let err = NonEmptyVec::<String>::try_from_inner(Vec::new()).unwrap_err();
+    assert_eq!(err, EmptyVec(Vec::new()));
+}
+
+#[test]
+fn sized_rejected_value() {
+    let mut v = Vec::with_capacity(16);
+    let EmptyVec(v_back) = NonEmptyVec::<u8>::try_from_inner(v).unwrap_err();
+    v = v_back;
+    assert_eq!(v.capacity(), 16);
+}
+
+#[test]
+fn unsized_() {
+    let v: &NonEmptySlice<u8> = <&NonEmptySlice<u8>>::from(&[1, 2][..]);
+    assert_eq!(v.as_ref(), &[1, 2]);
+    assert!(NonEmptySlice::<u8>::try_from_inner(&[]).is_err());
+}
+
+/// An item type which doesn't implement `Debug`.
+#[derive(Clone, PartialEq)]
+struct NonDebug;
+
+#[test]
+fn non_debug_item() {
+    // `EmptyVec<NonDebug>: Debug` doesn't hold, but impls which don't use
+    // the validation error are still available.
+    fn assert_sized<T>()
+    where
+        T: Deref<Target = Vec<NonDebug>>
+            + AsRef<Vec<NonDebug>>
+            + Into<Vec<NonDebug>>
+            + PartialEq<Vec<NonDebug>>,
+    {
+    }
+    fn assert_unsized<T>()
+    where
+        T: ?Sized + Deref<Target = [NonDebug]> + AsRef<[NonDebug]>,
+    {
+    }
+    assert_sized::<NonEmptyVec<NonDebug>>();
+    assert_unsized::<NonEmptySlice<NonDebug>>();
+}
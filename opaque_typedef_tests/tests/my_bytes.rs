@@ -0,0 +1,22 @@
+//! Tests for `my_bytes` types.
+
+extern crate opaque_typedef_tests;
+
+use opaque_typedef_tests::my_bytes::MyBytes;
+
+#[test]
+fn bitxor_by_fn() {
+    let lhs = MyBytes::new(&[0b1100, 0b1010, 0xff]);
+    let rhs = MyBytes::new(&[0b1010, 0b1010]);
+    let xor: Vec<u8> = lhs ^ rhs;
+    assert_eq!(xor, vec![0b0110, 0b0000]);
+    let xor_inner: Vec<u8> = lhs ^ &[0xff, 0x00, 0x0f][..];
+    assert_eq!(xor_inner, vec![0xf3, 0b1010, 0xf0]);
+}
+
+#[test]
+fn not_by_fn() {
+    let bytes = MyBytes::new(&[0x00, 0x0f, 0xff]);
+    let inverted: Vec<u8> = !bytes;
+    assert_eq!(inverted, vec![0xff, 0xf0, 0x00]);
+}
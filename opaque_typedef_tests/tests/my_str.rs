@@ -115,6 +115,16 @@ mod my_str {
         assert_eq!(ok_str, inner);
     }
 
+    #[test]
+    fn add_ref() {
+        let foo = MyStr::new("foo");
+        let bar = MyStr::new("bar");
+        let concat: String = foo + bar;
+        assert_eq!(concat, "foobar");
+        let concat: String = foo + "baz";
+        assert_eq!(concat, "foobaz");
+    }
+
     #[test]
     fn into_inner() {
         let ok_str = "foobar";
@@ -322,21 +332,27 @@ mod my_string {
     fn deref() {
         let ok_string = "foobar".to_owned();
         let my_string = MyString::from_string(ok_string);
+        let _: &MyStr = &my_string;
         let _: &str = &my_string;
     }
 
     #[test]
-    fn display() {
-        let ok_string = "foobar".to_owned();
-        let my_string = MyString::from_string(ok_string.clone());
-        assert_eq!(format!("{}", ok_string), format!("{}", my_string));
+    fn borrow_pair() {
+        use std::borrow::{Borrow, Cow};
+
+        let my_string = MyString::from_string("foobar".to_owned());
+        let my_str: &MyStr = my_string.borrow();
+        assert_eq!(my_str.to_owned(), my_string);
+        assert_eq!(MyString::from(my_str), my_string);
+        let cow = Cow::Borrowed(my_str);
+        assert_eq!(cow.into_owned(), my_string);
     }
 
     #[test]
-    fn deref_mut() {
+    fn display() {
         let ok_string = "foobar".to_owned();
-        let mut my_string = MyString::from_string(ok_string);
-        let _: &mut str = &mut my_string;
+        let my_string = MyString::from_string(ok_string.clone());
+        assert_eq!(format!("{}", ok_string), format!("{}", my_string));
     }
 
     #[test]
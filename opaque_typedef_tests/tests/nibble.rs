@@ -0,0 +1,45 @@
+//! Tests for `nibble` types.
+
+extern crate opaque_typedef_tests;
+
+use opaque_typedef_tests::nibble::Nibble;
+
+#[test]
+fn ok() {
+    let x = Nibble::from(4);
+    let y = Nibble::from(10);
+    assert_eq!(x + y, Nibble::from(14));
+    assert_eq!(y - x, Nibble::from(6));
+    assert_eq!(x * 3, Nibble::from(12));
+}
+
+#[test]
+fn out_of_range() {
+    let x = Nibble::from(9);
+    let y = Nibble::from(10);
+    assert_eq!(x + y, Nibble::from(3));
+    assert_eq!(x - y, Nibble::from(15));
+    assert_eq!(x * 2, Nibble::from(2));
+    assert_eq!(-x, Nibble::from(7));
+    assert_eq!(-Nibble::from(0), Nibble::from(0));
+}
+
+// The inner operations overflow `i8`, which panics in debug builds and wraps
+// in release builds if done by the regular operators.
+#[test]
+fn overflow() {
+    let x = Nibble::from(15);
+    // 15 * 100 = 1500 = 93 * 16 + 12.
+    assert_eq!(x * 100, Nibble::from(12));
+    // 15 * 127 = 1905 = 119 * 16 + 1.
+    assert_eq!(x * 127, Nibble::from(1));
+}
+
+#[test]
+fn add_assign() {
+    let mut x = Nibble::from(12);
+    x += Nibble::from(3);
+    assert_eq!(x, Nibble::from(15));
+    x += Nibble::from(3);
+    assert_eq!(x, Nibble::from(2));
+}
@@ -0,0 +1,178 @@
+//! Tests for `nonempty` types.
+
+extern crate opaque_typedef;
+extern crate opaque_typedef_tests;
+
+use std::convert::TryFrom;
+
+use opaque_typedef_tests::nonempty::{
+    EmptyError, NonEmptySlice, NonEmptyStr, NonEmptyString, NonEmptyVec,
+};
+
+mod nonempty_str {
+    use super::*;
+
+    #[test]
+    fn try_from_inner() {
+        let ok_str = "foobar";
+        let s = <&NonEmptyStr>::try_from(ok_str).unwrap();
+        assert_eq!(s.as_str(), ok_str);
+    }
+
+    #[test]
+    fn try_from_empty() {
+        assert_eq!(<&NonEmptyStr>::try_from(""), Err(EmptyError));
+    }
+
+    #[test]
+    fn try_from_inner_mut() {
+        let mut ok_string = "foobar".to_owned();
+        let s = <&mut NonEmptyStr>::try_from(ok_string.as_mut_str()).unwrap();
+        assert_eq!(s.as_str(), "foobar");
+        let mut empty = String::new();
+        assert_eq!(
+            <&mut NonEmptyStr>::try_from(empty.as_mut_str()).map(|s| s.as_str().to_owned()),
+            Err(EmptyError)
+        );
+    }
+
+    #[test]
+    fn try_into() {
+        use std::convert::TryInto;
+
+        let s: Result<&NonEmptyStr, _> = "foobar".try_into();
+        assert!(s.is_ok());
+    }
+}
+
+mod nonempty_string {
+    use super::*;
+
+    #[test]
+    fn try_from_inner() {
+        let s = NonEmptyString::try_from("foobar".to_owned()).unwrap();
+        assert_eq!(s.as_str(), "foobar");
+    }
+
+    #[test]
+    fn try_from_empty() {
+        assert_eq!(NonEmptyString::try_from(String::new()), Err(EmptyError));
+    }
+
+    #[test]
+    fn try_into() {
+        use std::convert::TryInto;
+
+        let s: Result<NonEmptyString, _> = String::new().try_into();
+        assert_eq!(s, Err(EmptyError));
+    }
+}
+
+mod pair {
+    use super::*;
+    use std::borrow::{Borrow, Cow};
+
+    #[test]
+    fn borrow() {
+        let s = NonEmptyString::try_from("foobar".to_owned()).unwrap();
+        let borrowed: &NonEmptyStr = s.borrow();
+        assert_eq!(borrowed.as_str(), "foobar");
+        let borrowed: &NonEmptyStr = s.as_ref();
+        assert_eq!(borrowed.as_str(), "foobar");
+    }
+
+    #[test]
+    fn deref() {
+        let s = NonEmptyString::try_from("foobar".to_owned()).unwrap();
+        let borrowed: &NonEmptyStr = &s;
+        assert_eq!(borrowed.as_str(), "foobar");
+        // Deref chain: `NonEmptyString` -> `NonEmptyStr` -> `str`.
+        assert_eq!(s.len(), 6);
+    }
+
+    #[test]
+    fn to_owned() {
+        let s = <&NonEmptyStr>::try_from("foobar").unwrap();
+        let owned: NonEmptyString = s.to_owned();
+        assert_eq!(owned.as_str(), "foobar");
+        let owned = NonEmptyString::from(s);
+        assert_eq!(owned.as_str(), "foobar");
+    }
+
+    #[test]
+    fn cow() {
+        let s = <&NonEmptyStr>::try_from("foobar").unwrap();
+        let cow = Cow::Borrowed(s);
+        let owned: NonEmptyString = cow.into_owned();
+        assert_eq!(owned.as_str(), "foobar");
+    }
+
+    #[test]
+    fn into_box() {
+        let s = NonEmptyString::try_from("foobar".to_owned()).unwrap();
+        let boxed = Box::<NonEmptyStr>::from(s);
+        assert_eq!(boxed.as_str(), "foobar");
+    }
+
+    #[test]
+    fn add_ref() {
+        let foo = <&NonEmptyStr>::try_from("foo").unwrap();
+        let bar = <&NonEmptyStr>::try_from("bar").unwrap();
+        let concat: NonEmptyString = foo + bar;
+        assert_eq!(concat.as_str(), "foobar");
+        let concat: NonEmptyString = foo + "";
+        assert_eq!(concat.as_str(), "foo");
+        let concat: NonEmptyString = "" + bar;
+        assert_eq!(concat.as_str(), "bar");
+    }
+
+    #[test]
+    fn generic() {
+        let v = NonEmptyVec::try_from(vec![1, 2, 3]).unwrap();
+        let slice: &NonEmptySlice<i32> = &v;
+        assert_eq!(slice.as_slice(), &[1, 2, 3]);
+        let owned: NonEmptyVec<i32> = slice.to_owned();
+        assert_eq!(owned, v);
+        let boxed: Box<NonEmptySlice<i32>> = v.into();
+        assert_eq!(boxed.as_slice(), &[1, 2, 3]);
+    }
+}
+
+mod serde {
+    extern crate serde_json;
+
+    use super::*;
+
+    #[test]
+    fn serialize() {
+        let s = <&NonEmptyStr>::try_from("foobar").unwrap();
+        assert_eq!(serde_json::to_string(s).unwrap(), r#""foobar""#);
+        let s = NonEmptyString::try_from("foobar".to_owned()).unwrap();
+        assert_eq!(serde_json::to_string(&s).unwrap(), r#""foobar""#);
+        let v = NonEmptyVec::try_from(vec![1, 2]).unwrap();
+        assert_eq!(serde_json::to_string(&v).unwrap(), "[1,2]");
+    }
+
+    #[test]
+    fn deserialize_borrowed() {
+        let json = r#""foobar""#.to_owned();
+        let s: &NonEmptyStr = serde_json::from_str(&json).unwrap();
+        assert_eq!(s.as_str(), "foobar");
+    }
+
+    #[test]
+    fn deserialize_owned() {
+        let s: NonEmptyString = serde_json::from_str(r#""foobar""#).unwrap();
+        assert_eq!(s.as_str(), "foobar");
+        let v: NonEmptyVec<i32> = serde_json::from_str("[1, 2]").unwrap();
+        assert_eq!(Into::<Vec<i32>>::into(v), vec![1, 2]);
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let err = serde_json::from_str::<&NonEmptyStr>(r#""""#).unwrap_err();
+        assert!(err.to_string().contains("Failed to create `NonEmptyStr`"));
+        assert!(serde_json::from_str::<NonEmptyString>(r#""""#).is_err());
+        assert!(serde_json::from_str::<NonEmptyVec<i32>>("[]").is_err());
+    }
+}
@@ -0,0 +1,97 @@
+//! Types normalizing the inner values before validation.
+
+extern crate opaque_typedef;
+extern crate opaque_typedef_tests;
+
+use std::borrow::Cow;
+use std::convert::TryFrom;
+
+use opaque_typedef::{OpaqueTypedef, OpaqueTypedefUnsized};
+use opaque_typedef_tests::normalize::{
+    Hostname, HostnameStr, InvalidHostname, NotTrimmed, Trimmed, TrimmedStr,
+};
+
+#[test]
+fn sized() {
+    let host = Hostname::try_from("Example.COM".to_owned()).unwrap();
+    assert_eq!(host.as_str(), "example.com");
+    assert_eq!(Hostname::from_inner("A".to_owned()).into_inner(), "a");
+    assert_eq!(Hostname::try_from("a_b".to_owned()), Err(InvalidHostname));
+}
+
+#[test]
+fn sized_ref() {
+    let input = "Example.COM".to_owned();
+    assert_eq!(Hostname::validate(&input), Ok(()));
+    assert_eq!(Hostname::try_from(&input).unwrap().as_str(), "example.com");
+    assert_eq!(Hostname::validate(&"a b".to_owned()), Err(InvalidHostname));
+}
+
+#[test]
+fn sized_or_return() {
+    let (v, e) = Hostname::try_from_inner_or_return("A B".to_owned()).unwrap_err();
+    assert_eq!(e, InvalidHostname);
+    // The given value is handed back as is.
+    assert_eq!(v, "A B");
+}
+
+#[test]
+fn sized_without_validator() {
+    let trimmed = Trimmed::from("  foo ".to_owned());
+    assert_eq!(AsRef::<String>::as_ref(&trimmed), "foo");
+    assert!(Trimmed::try_from_inner_or_return(" bar".to_owned()).is_ok());
+}
+
+#[test]
+fn unsized_cow_borrowed() {
+    let input = "example.com";
+    let host = HostnameStr::from_inner_cow(input).unwrap();
+    match host {
+        Cow::Borrowed(host) => assert_eq!(host.as_str() as *const str, input as *const str),
+        Cow::Owned(_) => panic!("Should be borrowed"),
+    }
+}
+
+#[test]
+fn unsized_cow_owned() {
+    let host = HostnameStr::from_inner_cow("Example.COM").unwrap();
+    match host {
+        Cow::Borrowed(_) => panic!("Should be owned"),
+        Cow::Owned(host) => assert_eq!(host.as_str(), "example.com"),
+    }
+}
+
+#[test]
+fn unsized_cow_invalid() {
+    assert_eq!(HostnameStr::from_inner_cow("a_b"), Err(InvalidHostname));
+    assert_eq!(HostnameStr::from_inner_cow("A_B"), Err(InvalidHostname));
+}
+
+#[test]
+fn unsized_not_normalized() {
+    // Conversions from `&Inner` reject values which are not normalized.
+    assert_eq!(HostnameStr::validate("Example.COM"), Err(InvalidHostname));
+    assert!(<&HostnameStr>::try_from("example.com").is_ok());
+}
+
+#[test]
+fn unsized_not_normalized_without_validator() {
+    assert_eq!(TrimmedStr::validate(" foo"), Err(NotTrimmed));
+    assert_eq!(<&TrimmedStr>::try_from("foo ").map(|_| ()), Err(NotTrimmed));
+    assert_eq!(<&TrimmedStr>::try_from("foo").unwrap().as_ref(), "foo");
+
+    match TrimmedStr::from_inner_cow(" foo ").unwrap() {
+        Cow::Borrowed(_) => panic!("Should be owned"),
+        Cow::Owned(s) => assert_eq!(AsRef::<String>::as_ref(&s), "foo"),
+    }
+    match TrimmedStr::from_inner_cow("foo").unwrap() {
+        Cow::Borrowed(s) => assert_eq!(AsRef::<str>::as_ref(s), "foo"),
+        Cow::Owned(_) => panic!("Should be borrowed"),
+    }
+}
+
+#[test]
+#[should_panic]
+fn unsized_not_normalized_panics() {
+    let _ = TrimmedStr::from_inner(" foo");
+}
@@ -0,0 +1,104 @@
+//! Tests for `not_nan` types.
+
+extern crate opaque_typedef;
+extern crate opaque_typedef_tests;
+
+mod common;
+
+use std::collections::{BTreeSet, HashSet};
+use std::{f32, f64};
+
+use opaque_typedef::float::NanError;
+use opaque_typedef::ops::{TryAdd, TryDiv};
+use opaque_typedef::OpaqueTypedef;
+use opaque_typedef_tests::not_nan::{CheckedNotNan32, NotNan64};
+
+use common::assert_eq_with_hash;
+
+mod not_nan64 {
+    use super::*;
+
+    #[test]
+    fn reject_nan() {
+        assert_eq!(NotNan64::try_from_inner(f64::NAN).err(), Some(NanError));
+        assert!(NotNan64::try_from_inner(f64::INFINITY).is_ok());
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_nan() {
+        let _ = NotNan64::from(f64::NAN);
+    }
+
+    #[test]
+    fn ops() {
+        let x = NotNan64::from(1.5);
+        let y = NotNan64::from(0.5);
+        assert_eq!(x + y, NotNan64::from(2.0));
+        assert_eq!(x - y, NotNan64::from(1.0));
+        assert_eq!(x * y, NotNan64::from(0.75));
+        assert_eq!(x / y, NotNan64::from(3.0));
+        assert_eq!(-x, NotNan64::from(-1.5));
+        assert_eq!(x + 1.0, NotNan64::from(2.5));
+        let mut z = x;
+        z += y;
+        assert_eq!(z, NotNan64::from(2.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn ops_nan() {
+        let _ = NotNan64::from(f64::INFINITY) - NotNan64::from(f64::INFINITY);
+    }
+
+    #[test]
+    fn cmp() {
+        assert!(NotNan64::from(-1.0) < NotNan64::from(0.0));
+        assert_eq!(NotNan64::from(0.0), NotNan64::from(-0.0));
+        assert!(NotNan64::from(1.0) == 1.0);
+        assert!(NotNan64::from(1.0) != f64::NAN);
+        assert!(NotNan64::from(1.0) < 2.0);
+        assert_eq!(NotNan64::from(1.0).partial_cmp(&f64::NAN), None);
+    }
+
+    #[test]
+    fn hash() {
+        assert_eq_with_hash(&NotNan64::from(0.0), &NotNan64::from(-0.0));
+        let set = [0.0, -0.0, 1.0]
+            .iter()
+            .map(|&v| NotNan64::from(v))
+            .collect::<HashSet<_>>();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn ord() {
+        let set = [3.0, -1.0, f64::INFINITY, 0.0, -0.0]
+            .iter()
+            .map(|&v| NotNan64::from(v))
+            .collect::<BTreeSet<_>>();
+        let values = set.into_iter().map(Into::into).collect::<Vec<f64>>();
+        assert_eq!(values, vec![-1.0, 0.0, 3.0, f64::INFINITY]);
+    }
+}
+
+mod checked_not_nan32 {
+    use super::*;
+
+    #[test]
+    fn ops() {
+        let x = CheckedNotNan32::from(1.0);
+        let y = CheckedNotNan32::from(2.0);
+        assert_eq!(x.try_add(y), Ok(CheckedNotNan32::from(3.0)));
+        assert_eq!(x.try_div(y), Ok(CheckedNotNan32::from(0.5)));
+    }
+
+    #[test]
+    fn ops_nan() {
+        let zero = CheckedNotNan32::from(0.0);
+        assert_eq!(zero.try_div(zero), Err(NanError));
+        let inf = CheckedNotNan32::from(f32::INFINITY);
+        let neg_inf = CheckedNotNan32::from(f32::NEG_INFINITY);
+        assert_eq!(inf.try_add(neg_inf), Err(NanError));
+    }
+}
@@ -0,0 +1,70 @@
+//! Tests for `percent` types.
+
+extern crate opaque_typedef;
+extern crate opaque_typedef_tests;
+
+use opaque_typedef::ops::{TryAdd, TryMul};
+use opaque_typedef_tests::percent::{CheckedPercent, OutOfRangeError, SaturatingPercent};
+
+mod checked {
+    use super::*;
+
+    #[test]
+    fn ok() {
+        let x = CheckedPercent::from(40);
+        let y = CheckedPercent::from(50);
+        assert_eq!(x.try_add(y), Ok(CheckedPercent::from(90)));
+        assert_eq!((&x).try_add(&y), Ok(CheckedPercent::from(90)));
+        assert_eq!(x.try_mul(2), Ok(CheckedPercent::from(80)));
+    }
+
+    #[test]
+    fn out_of_range() {
+        let x = CheckedPercent::from(60);
+        let y = CheckedPercent::from(50);
+        assert_eq!(x.try_add(y), Err(OutOfRangeError));
+        assert_eq!(x.try_mul(2), Err(OutOfRangeError));
+    }
+}
+
+mod saturate {
+    use super::*;
+
+    #[test]
+    fn ok() {
+        let x = SaturatingPercent::from(40);
+        let y = SaturatingPercent::from(50);
+        assert_eq!(x + y, SaturatingPercent::from(90));
+        assert_eq!(x * 2, SaturatingPercent::from(80));
+    }
+
+    #[test]
+    fn out_of_range() {
+        let x = SaturatingPercent::from(60);
+        let y = SaturatingPercent::from(50);
+        assert_eq!(x + y, SaturatingPercent::from(100));
+        assert_eq!(x * 2, SaturatingPercent::from(100));
+    }
+
+    // The inner operations overflow `u8`, which panics in debug builds and
+    // wraps in release builds if done by the regular operators.
+    #[test]
+    fn overflow() {
+        let x = SaturatingPercent::from(60);
+        assert_eq!(x * 5, SaturatingPercent::from(100));
+        assert_eq!(x * 255, SaturatingPercent::from(100));
+        assert_eq!(
+            SaturatingPercent::from(100) * 3,
+            SaturatingPercent::from(100)
+        );
+    }
+
+    #[test]
+    fn add_assign() {
+        let mut x = SaturatingPercent::from(60);
+        x += SaturatingPercent::from(30);
+        assert_eq!(x, SaturatingPercent::from(90));
+        x += SaturatingPercent::from(30);
+        assert_eq!(x, SaturatingPercent::from(100));
+    }
+}
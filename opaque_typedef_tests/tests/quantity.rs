@@ -0,0 +1,77 @@
+//! Tests for types with multiple fields.
+
+extern crate opaque_typedef;
+extern crate opaque_typedef_tests;
+
+use opaque_typedef::{OpaqueTypedef, OpaqueTypedefUnsized};
+use opaque_typedef_tests::quantity::{Labeled, Meter, Quantity, Second, TaggedStr};
+
+mod sized {
+    use super::*;
+
+    #[test]
+    fn phantom_data() {
+        let len = Quantity::<Meter>::from(3.0);
+        assert_eq!(len.value(), 3.0);
+        let inner: f64 = len.into();
+        assert_eq!(inner, 3.0);
+        assert_eq!(len.into_inner(), 3.0);
+    }
+
+    #[test]
+    fn ops() {
+        let a = Quantity::<Second>::from(3.0);
+        let b = Quantity::<Second>::from(1.5);
+        assert_eq!(a + b, Quantity::<Second>::from(4.5));
+        assert_eq!(a - b, Quantity::<Second>::from(1.5));
+        assert_eq!(a * 2.0, Quantity::<Second>::from(6.0));
+    }
+
+    #[test]
+    fn partial_eq_inner() {
+        let v = Quantity::<Meter>::from(1.0);
+        assert!(v == 1.0);
+        assert!(1.0 == v);
+        assert!(v != 2.0);
+    }
+
+    #[test]
+    fn fmt() {
+        let v = Quantity::<Meter>::from(1.5);
+        assert_eq!(format!("{}", v), "1.5");
+    }
+
+    #[test]
+    fn default_field() {
+        let v = Labeled::from(42);
+        assert_eq!(v.value(), 42);
+        assert_eq!(v.label(), "unlabeled");
+        assert_eq!(format!("{}", v), "42");
+        let inner: i32 = v.into();
+        assert_eq!(inner, 42);
+    }
+
+    #[test]
+    fn try_from_inner() {
+        let v = Labeled::try_from_inner(3).unwrap();
+        assert_eq!(v.label(), "unlabeled");
+        let v = unsafe { Labeled::from_inner_unchecked(4) };
+        assert_eq!(v.label(), "unlabeled");
+    }
+}
+
+mod unsized_ {
+    use super::*;
+
+    #[test]
+    fn phantom_data() {
+        let s = TaggedStr::<Meter>::new("hello");
+        assert_eq!(s.as_str(), "hello");
+        assert_eq!(s.as_inner(), "hello");
+        assert_eq!(format!("{}", s), "hello");
+        let inner: &str = s.into();
+        assert_eq!(inner, "hello");
+        let boxed: Box<TaggedStr<Meter>> = s.into();
+        assert_eq!(boxed.as_str(), "hello");
+    }
+}
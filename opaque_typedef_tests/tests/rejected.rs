@@ -0,0 +1,63 @@
+//! Types whose validators hand the rejected values back.
+
+extern crate opaque_typedef;
+extern crate opaque_typedef_tests;
+
+use std::convert::TryFrom;
+
+use opaque_typedef::OpaqueTypedef;
+use opaque_typedef_tests::atleast2items::VecAtLeast2Items;
+use opaque_typedef_tests::rejected::{NonEmptyBuf, NonEmptyBufError, TooShort, VecAtLeast3Items};
+
+#[test]
+fn rejected_try_from_inner() {
+    let v = VecAtLeast3Items::try_from(vec![1, 2, 3]).unwrap();
+    assert_eq!(v.len(), 3);
+    assert_eq!(
+        VecAtLeast3Items::<i32>::try_from_inner(vec![1]),
+        Err(TooShort(1))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Failed to create `VecAtLeast3Items`")]
+fn rejected_from_inner_panics() {
+    let _ = VecAtLeast3Items::from_inner(vec![1, 2]);
+}
+
+#[test]
+fn rejected_or_return() {
+    // The capacity is kept because the vector is not cloned.
+    let mut v = Vec::with_capacity(16);
+    v.push(1u8);
+    let (mut v, e) = VecAtLeast3Items::try_from_inner_or_return(v).unwrap_err();
+    assert_eq!(e, TooShort(1));
+    assert_eq!(v.capacity(), 16);
+    v.extend_from_slice(&[2, 3]);
+    let v = VecAtLeast3Items::try_from_inner_or_return(v).unwrap();
+    assert_eq!(v.as_ref(), &[1, 2, 3]);
+}
+
+#[test]
+fn auto_error_or_return() {
+    let (v, e) = NonEmptyBuf::try_from_inner_or_return(Vec::with_capacity(8)).unwrap_err();
+    assert_eq!(v.capacity(), 8);
+    assert_eq!(e.message(), "invalid `NonEmptyBuf` value");
+    assert_eq!(NonEmptyBuf::try_from_inner(Vec::new()).map(|_| ()), Err(e));
+    let v = NonEmptyBuf::try_from_inner_or_return(vec![0]).unwrap();
+    assert_eq!(v.into_inner(), vec![0]);
+}
+
+#[test]
+fn auto_error_type() {
+    let e: NonEmptyBufError = NonEmptyBuf::try_from_inner(Vec::new()).unwrap_err();
+    assert_eq!(e.to_string(), "invalid `NonEmptyBuf` value");
+}
+
+#[test]
+fn cloned_or_return() {
+    let (v, _) = VecAtLeast2Items::try_from_inner_or_return(vec![1]).unwrap_err();
+    assert_eq!(v, vec![1]);
+    let v = VecAtLeast2Items::try_from_inner_or_return(vec![1, 2]).unwrap();
+    assert_eq!(v.as_slice(), &[1, 2]);
+}
@@ -0,0 +1,61 @@
+//! Tests for secret-bearing types.
+
+extern crate opaque_typedef;
+extern crate opaque_typedef_tests;
+
+use opaque_typedef::secret::Zeroize;
+use opaque_typedef_tests::secret::{Password, Pin, SecretKey};
+
+#[test]
+fn redacted_debug() {
+    let password = Password::from("hunter2".to_owned());
+    assert_eq!(format!("{:?}", password), "Password(<redacted>)");
+    assert_eq!(format!("{:?}", Pin::from(1234)), "Pin(<redacted>)");
+    let key = SecretKey::from(vec![1, 2, 3]);
+    assert_eq!(format!("{:?}", key), "SecretKey(<redacted>)");
+}
+
+#[test]
+fn redacted_display() {
+    let password = Password::from("hunter2".to_owned());
+    assert_eq!(password.to_string(), "Password(<redacted>)");
+    assert_eq!(Pin::from(1234).to_string(), "Pin(<redacted>)");
+}
+
+#[test]
+fn into_inner() {
+    let password = Password::from("hunter2".to_owned());
+    let inner: String = password.into();
+    assert_eq!(inner, "hunter2");
+    let pin: u32 = Pin::from(1234).into();
+    assert_eq!(pin, 1234);
+}
+
+#[test]
+fn forced_as_ref() {
+    let key = SecretKey::from(vec![1, 2, 3]);
+    let bytes: &Vec<u8> = key.as_ref();
+    assert_eq!(bytes, &[1, 2, 3]);
+}
+
+#[test]
+fn eq() {
+    let password = Password::from("hunter2".to_owned());
+    assert!(password == Password::from("hunter2".to_owned()));
+    assert!(password != Password::from("hunter3".to_owned()));
+}
+
+#[test]
+fn zeroize() {
+    let mut s = "hunter2".to_owned();
+    s.zeroize();
+    assert!(s.is_empty());
+
+    let mut v = vec![1u8, 2, 3];
+    v.zeroize();
+    assert!(v.is_empty());
+
+    let mut n = 1234u32;
+    n.zeroize();
+    assert_eq!(n, 0);
+}
@@ -0,0 +1,61 @@
+//! Tests for secret-bearing types compared in constant time.
+
+extern crate opaque_typedef;
+extern crate opaque_typedef_tests;
+
+use std::collections::HashSet;
+
+use opaque_typedef_tests::secret_token::{ApiToken, MacTag};
+
+mod api_token {
+    use super::*;
+
+    fn token(s: &str) -> ApiToken {
+        ApiToken::from(s.to_owned())
+    }
+
+    #[test]
+    fn eq_self() {
+        assert_eq!(token("secret"), token("secret"));
+        assert_ne!(token("secret"), token("secreT"));
+        assert_ne!(token("secret"), token("secret!"));
+        assert_ne!(token(""), token("secret"));
+        assert_eq!(token(""), token(""));
+    }
+
+    #[test]
+    fn eq_inner() {
+        let inner = "secret".to_owned();
+        assert!(token("secret") == inner);
+        assert!(inner == token("secret"));
+        assert!(token("Secret") != inner);
+    }
+
+    #[test]
+    fn hash_set() {
+        let mut set = HashSet::new();
+        assert!(set.insert(token("secret")));
+        assert!(!set.insert(token("secret")));
+        assert!(set.contains(&token("secret")));
+        assert!(!set.contains(&token("other")));
+    }
+}
+
+mod mac_tag {
+    use super::*;
+
+    #[test]
+    fn eq_self() {
+        assert_eq!(MacTag::new(&[1, 2, 3]), MacTag::new(&[1, 2, 3]));
+        assert_ne!(MacTag::new(&[1, 2, 3]), MacTag::new(&[1, 2, 4]));
+        assert_ne!(MacTag::new(&[1, 2, 3]), MacTag::new(&[1, 2]));
+    }
+
+    #[test]
+    fn eq_inner() {
+        let inner: &[u8] = &[0xde, 0xad];
+        assert!(MacTag::new(&[0xde, 0xad]) == inner);
+        assert!(*inner == *MacTag::new(&[0xde, 0xad]));
+        assert!(*MacTag::new(&[0xbe, 0xef]) != *inner);
+    }
+}
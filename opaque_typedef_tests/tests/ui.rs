@@ -0,0 +1,11 @@
+//! Tests for diagnostics of invalid attributes.
+//!
+//! To update the expected outputs, run the tests with `TRYBUILD=overwrite`.
+
+extern crate trybuild;
+
+#[test]
+fn ui() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,15 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+fn validate_even(v: u32) -> Result<u32, ()> {
+    if v % 2 == 0 { Ok(v) } else { Err(()) }
+}
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(derive(AddAssign(Self)))]
+#[opaque_typedef(validation(validator = "validate_even", error_type = "()"))]
+#[opaque_typedef(assign_ops = "in_place")]
+pub struct Even(u32);
+
+fn main() {}
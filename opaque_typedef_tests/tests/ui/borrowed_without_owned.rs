@@ -0,0 +1,13 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+#[derive(OpaqueTypedefUnsized)]
+#[repr(C)]
+pub struct NameStr(str);
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(borrowed = "NameStr")]
+pub struct Name(String);
+
+fn main() {}
@@ -0,0 +1,10 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(derive(FromInner, PartialEq(Self), PartialOrd(Self)))]
+#[opaque_typedef(cmp(constant_time))]
+pub struct Token(Vec<u8>);
+
+fn main() {}
@@ -0,0 +1,10 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(derive(Display, FromInner))]
+#[opaque_typedef(display_fmt = "#{")]
+pub struct Id(u32);
+
+fn main() {}
@@ -0,0 +1,13 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(derive(FromInner))]
+pub struct Id {
+    #[opaque_typedef(inner)]
+    value: u32,
+    tag: u8,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+use std::fmt;
+
+fn fmt_id(v: &u32, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "#{}", v)
+}
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(derive(Display, FromInner, FromStr))]
+#[opaque_typedef(display = "fmt_id")]
+pub struct Id(u32);
+
+fn main() {}
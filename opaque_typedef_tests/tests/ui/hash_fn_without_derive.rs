@@ -0,0 +1,10 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(derive(FromInner))]
+#[opaque_typedef(cmp(hash = "(|v: &u32, state: &mut std::collections::hash_map::DefaultHasher| ())"))]
+pub struct Id(u32);
+
+fn main() {}
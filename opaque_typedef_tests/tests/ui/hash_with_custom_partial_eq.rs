@@ -0,0 +1,10 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(derive(FromInner, Hash, PartialEq(Self)))]
+#[opaque_typedef(cmp(partial_eq = "(|a: &String, b: &String| a.eq_ignore_ascii_case(b))"))]
+pub struct Caseless(String);
+
+fn main() {}
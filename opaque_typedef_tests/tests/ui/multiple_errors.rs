@@ -0,0 +1,10 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(derive(Frobnicate, DefaultRef, FromInner))]
+#[opaque_typedef(validation(validator = "not a path", error_type = "()"))]
+pub struct Id(u32);
+
+fn main() {}
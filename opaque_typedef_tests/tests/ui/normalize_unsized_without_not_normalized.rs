@@ -0,0 +1,35 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+use std::borrow::Cow;
+
+fn lowercase(s: &str) -> Cow<str> {
+    Cow::Owned(s.to_lowercase())
+}
+
+#[derive(Debug)]
+pub struct InvalidName;
+
+fn check_name(s: &str) -> Result<(), InvalidName> {
+    if s.is_empty() {
+        Err(InvalidName)
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, OpaqueTypedef)]
+pub struct Name(String);
+
+#[derive(OpaqueTypedefUnsized)]
+#[repr(C)]
+#[opaque_typedef(validation(
+    normalize = "lowercase",
+    check = "check_name",
+    error_type = "InvalidName"
+))]
+#[opaque_typedef(owned = "Name")]
+pub struct NameStr(str);
+
+fn main() {}
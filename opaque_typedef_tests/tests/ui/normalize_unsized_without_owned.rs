@@ -0,0 +1,27 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+use std::borrow::Cow;
+
+fn lowercase(s: &str) -> Cow<str> {
+    Cow::Owned(s.to_lowercase())
+}
+
+#[derive(Debug)]
+pub struct NotLowercase;
+
+fn not_lowercase(_: &str) -> NotLowercase {
+    NotLowercase
+}
+
+#[derive(OpaqueTypedefUnsized)]
+#[repr(C)]
+#[opaque_typedef(validation(
+    normalize = "lowercase",
+    not_normalized = "not_lowercase",
+    error_type = "NotLowercase"
+))]
+pub struct LowerStr(str);
+
+fn main() {}
@@ -0,0 +1,24 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+#[derive(Debug)]
+pub struct NotLowercase;
+
+fn lowercase(s: String) -> String {
+    s.to_lowercase()
+}
+
+fn not_lowercase(_: &str) -> NotLowercase {
+    NotLowercase
+}
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(validation(
+    normalize = "lowercase",
+    not_normalized = "not_lowercase",
+    error_type = "NotLowercase"
+))]
+pub struct Name(String);
+
+fn main() {}
@@ -0,0 +1,17 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+#[derive(Debug)]
+pub struct NotLowercase;
+
+fn not_lowercase(_: &str) -> NotLowercase {
+    NotLowercase
+}
+
+#[derive(OpaqueTypedefUnsized)]
+#[repr(C)]
+#[opaque_typedef(validation(not_normalized = "not_lowercase", error_type = "NotLowercase"))]
+pub struct NameStr(str);
+
+fn main() {}
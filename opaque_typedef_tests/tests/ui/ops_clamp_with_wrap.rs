@@ -0,0 +1,14 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+fn clamp_counter(v: u8) -> u8 {
+    v.min(100)
+}
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(derive(Add(Self)))]
+#[opaque_typedef(ops(on_invalid = "wrap", clamp = "clamp_counter"))]
+pub struct Counter(u8);
+
+fn main() {}
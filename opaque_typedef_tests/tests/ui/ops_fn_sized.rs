@@ -0,0 +1,14 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+fn add_counts(lhs: u32, rhs: u32) -> u32 {
+    lhs.saturating_add(rhs)
+}
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(derive(Add(Self)))]
+#[opaque_typedef(ops(add = "add_counts"))]
+pub struct Count(u32);
+
+fn main() {}
@@ -0,0 +1,10 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+#[derive(OpaqueTypedefUnsized)]
+#[repr(C)]
+#[opaque_typedef(ops(on_invalid = "checked"))]
+pub struct Bytes([u8]);
+
+fn main() {}
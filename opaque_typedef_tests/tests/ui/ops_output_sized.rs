@@ -0,0 +1,10 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(derive(Add(Self)))]
+#[opaque_typedef(ops(output = "inner"))]
+pub struct Count(u32);
+
+fn main() {}
@@ -0,0 +1,10 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(derive(Add(Self)))]
+#[opaque_typedef(ops(on_invalid = "wrap"))]
+pub struct Counter(u8);
+
+fn main() {}
@@ -0,0 +1,16 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+/// Not a zero-sized type, but named `PhantomData`.
+pub struct PhantomData<T>(T);
+
+#[derive(OpaqueTypedefUnsized)]
+#[repr(C)]
+pub struct Tagged<T> {
+    _tag: PhantomData<T>,
+    #[opaque_typedef(inner)]
+    inner: str,
+}
+
+fn main() {}
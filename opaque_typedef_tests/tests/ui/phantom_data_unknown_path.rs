@@ -0,0 +1,17 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+mod marker {
+    pub struct PhantomData<T>(pub T);
+}
+
+#[derive(OpaqueTypedefUnsized)]
+#[repr(C)]
+pub struct Tagged<T> {
+    _tag: marker::PhantomData<T>,
+    #[opaque_typedef(inner)]
+    inner: str,
+}
+
+fn main() {}
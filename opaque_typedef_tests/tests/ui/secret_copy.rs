@@ -0,0 +1,10 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+#[derive(Clone, Copy, OpaqueTypedef)]
+#[opaque_typedef(derive(FromInner))]
+#[opaque_typedef(secret)]
+pub struct Pin(u32);
+
+fn main() {}
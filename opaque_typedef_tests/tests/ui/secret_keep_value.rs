@@ -0,0 +1,14 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+fn is_long(s: &str) -> bool {
+    s.len() >= 8
+}
+
+#[derive(Debug, OpaqueTypedef)]
+#[opaque_typedef(validation(validator = "is_long", error = "auto", keep_value))]
+#[opaque_typedef(secret)]
+pub struct Password(String);
+
+fn main() {}
@@ -0,0 +1,8 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+#[derive(OpaqueTypedefUnsized)]
+pub struct Name(str);
+
+fn main() {}
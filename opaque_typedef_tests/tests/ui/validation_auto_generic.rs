@@ -0,0 +1,13 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+fn is_nonempty<T>(v: &[T]) -> bool {
+    !v.is_empty()
+}
+
+#[derive(Debug, OpaqueTypedef)]
+#[opaque_typedef(validation(validator = "is_nonempty", error = "auto"))]
+pub struct NonEmptyVec<T>(Vec<T>);
+
+fn main() {}
@@ -0,0 +1,17 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+fn check_id(v: &u32) -> Result<(), ()> {
+    if *v == 0 { Err(()) } else { Ok(()) }
+}
+
+fn validate_id(v: u32) -> Result<u32, ()> {
+    check_id(&v).map(|_| v)
+}
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(validation(validator = "validate_id", check = "check_id", error_type = "()"))]
+pub struct Id(u32);
+
+fn main() {}
@@ -0,0 +1,9 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(validation(error_type = "()"))]
+pub struct Id(u32);
+
+fn main() {}
@@ -0,0 +1,13 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+fn validate_id(v: u32) -> Result<u32, ()> {
+    if v == 0 { Err(()) } else { Ok(v) }
+}
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(validation(validator = "validate_id", error_type = "()", keep_value))]
+pub struct Id(u32);
+
+fn main() {}
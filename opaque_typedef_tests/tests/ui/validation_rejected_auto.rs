@@ -0,0 +1,13 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+fn is_nonzero(v: &u32) -> bool {
+    *v != 0
+}
+
+#[derive(Debug, OpaqueTypedef)]
+#[opaque_typedef(validation(validator = "is_nonzero", error = "auto", rejected))]
+pub struct Id(u32);
+
+fn main() {}
@@ -0,0 +1,10 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(float(not_nan))]
+#[opaque_typedef(validation(rejected))]
+pub struct NotNan(f64);
+
+fn main() {}
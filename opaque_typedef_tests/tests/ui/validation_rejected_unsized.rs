@@ -0,0 +1,14 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+fn validate_name(s: &str) -> Result<&str, ()> {
+    if s.is_empty() { Err(()) } else { Ok(s) }
+}
+
+#[derive(OpaqueTypedefUnsized)]
+#[repr(C)]
+#[opaque_typedef(validation(validator = "validate_name", error_type = "()", rejected))]
+pub struct Name(str);
+
+fn main() {}
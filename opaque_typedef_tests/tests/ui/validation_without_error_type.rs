@@ -0,0 +1,13 @@
+extern crate opaque_typedef;
+#[macro_use]
+extern crate opaque_typedef_macros;
+
+fn check_id(v: &u32) -> Result<(), ()> {
+    if *v == 0 { Err(()) } else { Ok(()) }
+}
+
+#[derive(OpaqueTypedef)]
+#[opaque_typedef(validation(check = "check_id"))]
+pub struct Id(u32);
+
+fn main() {}
@@ -0,0 +1,102 @@
+//! String types compared case-insensitively for ASCII characters, by the
+//! `ascii_case_insensitive` preset.
+
+extern crate opaque_typedef;
+extern crate opaque_typedef_tests;
+
+mod common;
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashSet};
+
+use opaque_typedef_tests::uncased::{Uncased, UncasedStr};
+
+use common::assert_eq_with_hash;
+
+mod unsized_ {
+    use super::*;
+
+    #[test]
+    fn eq_self() {
+        assert_eq!(UncasedStr::new("Hello"), UncasedStr::new("hELLO"));
+        assert_ne!(UncasedStr::new("Hello"), UncasedStr::new("Hello!"));
+        // Non-ASCII characters are compared as is.
+        assert_ne!(UncasedStr::new("\u{e9}"), UncasedStr::new("\u{c9}"));
+    }
+
+    #[test]
+    fn ord_self() {
+        assert_eq!(
+            UncasedStr::new("apple").cmp(UncasedStr::new("BANANA")),
+            Ordering::Less
+        );
+        assert_eq!(
+            UncasedStr::new("Apple").cmp(UncasedStr::new("aPPLE")),
+            Ordering::Equal
+        );
+        assert!(UncasedStr::new("Zebra") > UncasedStr::new("apple"));
+    }
+
+    #[test]
+    fn hash_consistent_with_eq() {
+        assert_eq_with_hash(UncasedStr::new("Hello"), UncasedStr::new("hELLO"));
+    }
+
+    #[test]
+    fn cmp_inner() {
+        assert!(*UncasedStr::new("Hello") == *"HELLO");
+        assert!(*"hello" == *UncasedStr::new("HeLLo"));
+        assert!(UncasedStr::new("Hello") == "HELLO");
+        assert!(*UncasedStr::new("apple") < *"BANANA");
+        assert!(*"BANANA" > *UncasedStr::new("apple"));
+    }
+
+    #[test]
+    fn cmp_inner_cow() {
+        let cow: Cow<str> = Cow::Borrowed("HELLO");
+        assert!(*UncasedStr::new("Hello") == cow);
+        assert!(cow == *UncasedStr::new("hello"));
+        assert!(*UncasedStr::new("apple") < cow);
+    }
+}
+
+mod sized {
+    use super::*;
+
+    fn uncased(s: &str) -> Uncased {
+        Uncased::from(s.to_owned())
+    }
+
+    #[test]
+    fn eq_self() {
+        assert_eq!(uncased("Hello"), uncased("hELLO"));
+        assert_ne!(uncased("Hello"), uncased("world"));
+    }
+
+    #[test]
+    fn cmp_inner() {
+        let inner = "HELLO".to_owned();
+        assert!(uncased("hello") == inner);
+        assert!(inner == uncased("hello"));
+        assert!(uncased("apple") < inner);
+    }
+
+    #[test]
+    fn collections() {
+        let mut hash_set = HashSet::new();
+        assert!(hash_set.insert(uncased("Hello")));
+        assert!(!hash_set.insert(uncased("HELLO")));
+        assert!(hash_set.contains(&uncased("hello")));
+
+        let btree_set = ["b", "A", "a", "C"]
+            .iter()
+            .map(|s| uncased(s))
+            .collect::<BTreeSet<_>>();
+        let items = btree_set
+            .iter()
+            .map(|s| s.to_ascii_lowercase())
+            .collect::<Vec<_>>();
+        assert_eq!(items, vec!["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,71 @@
+//! Types validated by composable validators.
+
+extern crate opaque_typedef;
+extern crate opaque_typedef_tests;
+
+use std::convert::TryFrom;
+
+use opaque_typedef::validator::{Either, NotError, Validator, ValidatorExt};
+use opaque_typedef::{OpaqueTypedef, OpaqueTypedefUnsized};
+use opaque_typedef_tests::validator::{
+    EmptyError, InvalidName, MaxLen, NonEmpty, ShortName, ShortNameStr, TooLong,
+};
+
+#[test]
+fn combinators() {
+    let v = NonEmpty.and(MaxLen(3));
+    assert_eq!(v.validate("abc"), Ok(()));
+    assert_eq!(v.validate(""), Err(Either::Left(EmptyError)));
+    assert_eq!(
+        v.validate("abcd"),
+        Err(Either::Right(TooLong { max: 3, len: 4 }))
+    );
+
+    let v = NonEmpty.not().or(MaxLen(1));
+    assert_eq!(v.validate(""), Ok(()));
+    assert_eq!(v.validate("a"), Ok(()));
+    assert_eq!(v.validate("ab"), Err(TooLong { max: 1, len: 2 }));
+
+    assert_eq!(
+        Validator::<str>::validate(&NonEmpty.not(), "a"),
+        Err(NotError)
+    );
+    let v = MaxLen(1).map_err(|e: TooLong| e.len);
+    assert_eq!(v.validate("abc"), Err(3));
+}
+
+#[test]
+fn no_conflict_with_std_ops() {
+    use std::ops::Not;
+
+    // `ValidatorExt` is implemented only for validators, so `bool::not` is not ambiguous.
+    assert!(false.not());
+}
+
+#[test]
+fn sized() {
+    assert!(ShortName::try_from("alice".to_owned()).is_ok());
+    assert_eq!(
+        ShortName::try_from(String::new()),
+        Err(Either::Left(EmptyError))
+    );
+    assert_eq!(
+        ShortName::validate(&"alice_and_bob".to_owned()),
+        Err(Either::Right(TooLong { max: 8, len: 13 }))
+    );
+    let name = "bob".to_owned();
+    assert_eq!(ShortName::try_from(&name).unwrap().as_ref(), &name);
+}
+
+#[test]
+fn unsized_() {
+    assert_eq!(
+        <&ShortNameStr>::try_from("alice").unwrap().as_ref(),
+        "alice"
+    );
+    assert_eq!(ShortNameStr::validate(""), Err(InvalidName));
+    assert_eq!(
+        ShortNameStr::try_from_inner("alice_and_bob"),
+        Err(InvalidName)
+    );
+}